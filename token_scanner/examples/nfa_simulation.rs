@@ -0,0 +1,49 @@
+//! Compares `FiniteAutomaton::accept` with `BitsetNFA` on `(a|b)*a(a|b){n}`.
+//!
+//! Run with `cargo run --release --example nfa_simulation`.
+use maplit::{hashmap, hashset};
+use std::time::Instant;
+use token_scanner::automaton::bitset_nfa::BitsetNFA;
+use token_scanner::{FiniteAutomaton, NFA};
+
+fn main() {
+    let n = 20;
+    let mut transition_map = hashmap! {
+        0 => hashmap! {Some('a') => hashset! {0, 1}, Some('b') => hashset! {0}},
+    };
+    for state in 1..=n {
+        transition_map.insert(
+            state,
+            hashmap! {Some('a') => hashset! {state + 1}, Some('b') => hashset! {state + 1}},
+        );
+    }
+    let nfa = NFA::from_map(0, hashset! {n + 1}, transition_map);
+    let input: String = (0..100_000u32)
+        .map(|i| {
+            if i.wrapping_mul(2_654_435_761) >> 31 == 0 {
+                'a'
+            } else {
+                'b'
+            }
+        })
+        .collect();
+
+    let start = Instant::now();
+    let generic = nfa.accept(input.chars());
+    let generic_time = start.elapsed();
+
+    let start = Instant::now();
+    let runner = BitsetNFA::new(&nfa);
+    let setup_time = start.elapsed();
+    let start = Instant::now();
+    let bitset = runner.accept(input.chars());
+    let bitset_time = start.elapsed();
+
+    assert_eq!(generic, bitset);
+    println!("input length: {}, accepted: {}", input.len(), generic);
+    println!("FiniteAutomaton::accept: {:?}", generic_time);
+    println!(
+        "BitsetNFA::accept: {:?} (setup {:?})",
+        bitset_time, setup_time
+    );
+}
@@ -0,0 +1,20 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use token_scanner::formats::rust_codegen::CodegenStyle;
+
+include!("dfas.rs");
+
+fn main() {
+    println!("cargo:rerun-if-changed=dfas.rs");
+    let out_dir = env::var("OUT_DIR").unwrap();
+    for (name, dfa) in dfas() {
+        for (style, suffix) in [
+            (CodegenStyle::Tables, "tables"),
+            (CodegenStyle::Match, "match"),
+        ] {
+            let path = Path::new(&out_dir).join(format!("{}_{}.rs", name, suffix));
+            fs::write(path, dfa.to_rust_source(style)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// The DFAs generated into scanners by `build.rs`, also loaded by the tests to compare against.
+use std::collections::HashMap;
+use token_scanner::examples::aho_corasick::AhoCorasick;
+use token_scanner::{pre_defined_fa, DFA};
+
+/// Decimal numbers like `-12` and `3.25`. `_` is read by the DFA but never accepted.
+fn number() -> DFA<char, usize> {
+    let (start, sign, integer, dot, fraction, dead) = (0, 1, 2, 3, 4, 5);
+    let mut transition_map = HashMap::new();
+    for state in start..=dead {
+        let mut map = HashMap::new();
+        for c in "0123456789.-_".chars() {
+            let to_state = match (state, c) {
+                (0, '-') => sign,
+                (0..=2, '0'..='9') => integer,
+                (2, '.') => dot,
+                (3..=4, '0'..='9') => fraction,
+                _ => dead,
+            };
+            map.insert(c, to_state);
+        }
+        transition_map.insert(state, map);
+    }
+    DFA::from_map(start, [integer, fraction].iter().cloned().collect(), transition_map)
+}
+
+pub fn dfas() -> Vec<(&'static str, DFA<char, usize>)> {
+    vec![
+        ("div_by_3", pre_defined_fa::bin_str_div_by_3().relabeled()),
+        (
+            "keywords",
+            AhoCorasick::new(&["if", "else", "elif", "for", "fn", "λ"]).into_dfa(),
+        ),
+        ("number", number()),
+    ]
+}
@@ -0,0 +1,16 @@
+//! Scanners generated by `build.rs` from the DFAs of `dfas.rs`, in both styles.
+
+macro_rules! generated {
+    ($name:ident, $file:literal) => {
+        pub mod $name {
+            include!(concat!(env!("OUT_DIR"), "/", $file));
+        }
+    };
+}
+
+generated!(div_by_3_tables, "div_by_3_tables.rs");
+generated!(div_by_3_match, "div_by_3_match.rs");
+generated!(keywords_tables, "keywords_tables.rs");
+generated!(keywords_match, "keywords_match.rs");
+generated!(number_tables, "number_tables.rs");
+generated!(number_match, "number_match.rs");
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use codegen_fixture::*;
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+    use token_scanner::FiniteAutomaton;
+
+    include!("../dfas.rs");
+
+    /// The `accept` and `next_token` functions of a generated scanner.
+    type Scanner = (fn(&str) -> bool, fn(&str) -> Option<usize>);
+
+    fn scanners(name: &str) -> [Scanner; 2] {
+        match name {
+            "div_by_3" => [
+                (div_by_3_tables::accept, div_by_3_tables::next_token),
+                (div_by_3_match::accept, div_by_3_match::next_token),
+            ],
+            "keywords" => [
+                (keywords_tables::accept, keywords_tables::next_token),
+                (keywords_match::accept, keywords_match::next_token),
+            ],
+            "number" => [
+                (number_tables::accept, number_tables::next_token),
+                (number_match::accept, number_match::next_token),
+            ],
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn generated_scanners_match_dfas() {
+        let mut rng = rand::thread_rng();
+        for (name, dfa) in dfas() {
+            let mut alphabets: Vec<char> = dfa.alphabets().iter().cloned().collect();
+            alphabets.sort_unstable();
+            // Characters the DFA does not read.
+            let unknown = ['#', '😀'];
+            for _ in 0..2000 {
+                let len = rng.gen_range(0, 12);
+                let input: String = (0..len)
+                    .map(|_| {
+                        if rng.gen_range(0, 20) == 0 {
+                            *unknown.choose(&mut rng).unwrap()
+                        } else {
+                            *alphabets.choose(&mut rng).unwrap()
+                        }
+                    })
+                    .collect();
+                let known = input.chars().all(|c| alphabets.contains(&c));
+                let expected_len = match dfa.longest_match(input.chars()) {
+                    None | Some(0) => None,
+                    Some(len) => Some(input.chars().take(len).map(char::len_utf8).sum()),
+                };
+                for (accept, next_token) in scanners(name).iter() {
+                    if known {
+                        assert_eq!(accept(&input), dfa.accept(input.chars()), "{:?}", input);
+                    } else {
+                        assert!(!accept(&input), "{:?}", input);
+                    }
+                    assert_eq!(next_token(&input), expected_len, "{} {:?}", name, input);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn dead_states_stop_scanning() {
+        assert_eq!(number_tables::next_token("-12.5.3"), Some(5));
+        assert_eq!(number_match::next_token("-12.x"), Some(3));
+        assert_eq!(number_match::next_token("_1"), None);
+        assert_eq!(
+            number_tables::next_state(number_tables::START_STATE, '_'),
+            None
+        );
+        assert!(keywords_match::accept("fλ"));
+        assert_eq!(keywords_tables::next_token("elsewhere"), Some(4));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{FiniteAutomaton, StateIdentifier};
+use std::collections::HashMap;
+
+/// A partition of the alphabets of a finite automaton into classes of alphabets that have the same
+/// transitions from every state, so the automaton only needs one transition per class.
+///
+/// Classes are numbered by their smallest alphabet, and alphabets within a class are sorted, both
+/// ordered by their labels.
+///
+/// ```
+/// use maplit::{hashmap, hashset};
+/// use token_scanner::{FiniteAutomaton, DFA};
+///
+/// let dfa = DFA::from_map(
+///     0,
+///     hashset! {1},
+///     hashmap! {
+///         0 => hashmap! {'a' => 1, 'b' => 1, 'c' => 0},
+///         1 => hashmap! {'a' => 1, 'b' => 1, 'c' => 1},
+///     },
+/// );
+/// let classes = dfa.alphabet_classes();
+/// assert_eq!(classes.classes(), &[vec!['a', 'b'], vec!['c']]);
+/// assert_eq!(classes.class(&'b'), Some(0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AlphabetClasses<T>
+where
+    T: NoneEmptyAlphabet,
+{
+    classes: Vec<Vec<T>>,
+    alphabet_to_class: HashMap<T, usize>,
+}
+
+impl<T> AlphabetClasses<T>
+where
+    T: NoneEmptyAlphabet,
+{
+    pub fn new<U, F>(fa: &F) -> Self
+    where
+        U: StateIdentifier,
+        F: FiniteAutomaton<T, U> + ?Sized,
+    {
+        let states: Vec<&U> = fa.states().iter().collect();
+        let state_to_idx: HashMap<&U, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (*state, idx))
+            .collect();
+        let mut alphabets: Vec<&T> = fa.alphabets().iter().collect();
+        alphabets.sort_by_cached_key(|alphabet| (alphabet.to_string(), format!("{:?}", alphabet)));
+
+        let mut classes: Vec<Vec<T>> = vec![];
+        let mut signature_to_class: HashMap<Vec<Vec<usize>>, usize> = HashMap::new();
+        let mut alphabet_to_class = HashMap::new();
+        for alphabet in alphabets {
+            let signature: Vec<Vec<usize>> = states
+                .iter()
+                .map(|state| {
+                    let mut to_states: Vec<usize> = fa
+                        .transition((*state).clone(), Some(alphabet.clone()))
+                        .iter()
+                        .map(|to_state| *state_to_idx.get(to_state).unwrap())
+                        .collect();
+                    to_states.sort_unstable();
+                    to_states
+                })
+                .collect();
+            let class = *signature_to_class.entry(signature).or_insert_with(|| {
+                classes.push(vec![]);
+                classes.len() - 1
+            });
+            classes[class].push(alphabet.clone());
+            alphabet_to_class.insert(alphabet.clone(), class);
+        }
+        Self {
+            classes,
+            alphabet_to_class,
+        }
+    }
+
+    /// Number of classes.
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Returns the class of `alphabet`, or `None` if it is not an alphabet of the automaton.
+    pub fn class(&self, alphabet: &T) -> Option<usize> {
+        self.alphabet_to_class.get(alphabet).cloned()
+    }
+
+    pub fn classes(&self) -> &[Vec<T>] {
+        &self.classes
+    }
+
+    /// The smallest alphabet in `class`, whose transitions stand for the whole class.
+    pub fn representative(&self, class: usize) -> &T {
+        &self.classes[class][0]
+    }
+}
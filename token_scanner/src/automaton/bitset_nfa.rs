@@ -0,0 +1,142 @@
+use crate::automaton::indexed_nfa::IndexedNFA;
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{FiniteAutomaton, StateIdentifier};
+
+const WORD_BITS: usize = 64;
+
+/// Set of states `0..n`, one bit per state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    fn insert(&mut self, idx: usize) {
+        self.words[idx / WORD_BITS] |= 1 << (idx % WORD_BITS);
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        for (word, other_word) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other_word;
+        }
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .any(|(word, other_word)| word & other_word != 0)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    fn clear(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = 0;
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(idx * WORD_BITS + bit)
+            })
+        })
+    }
+}
+
+/// Simulates a finite automaton on sets of active states stored as bitsets, with epsilon closures
+/// computed once up front. Every symbol takes time linear in the number of active states and
+/// their transitions, without allocating, unlike `FiniteAutomaton::accept`.
+///
+/// ```
+/// use token_scanner::automaton::bitset_nfa::BitsetNFA;
+/// use token_scanner::pre_defined_fa;
+///
+/// let runner = BitsetNFA::new(&pre_defined_fa::bin_str_div_by_3());
+/// assert!(runner.accept("1001".chars()));
+/// assert!(!runner.accept("1000".chars()));
+/// ```
+pub struct BitsetNFA<T>
+where
+    T: NoneEmptyAlphabet,
+{
+    nfa: IndexedNFA<T>,
+    start_states: BitSet,
+    accepted_states: BitSet,
+    closures: Vec<BitSet>,
+}
+
+impl<T> BitsetNFA<T>
+where
+    T: NoneEmptyAlphabet,
+{
+    pub fn new<U, F>(fa: &F) -> Self
+    where
+        U: StateIdentifier,
+        F: FiniteAutomaton<T, U> + ?Sized,
+    {
+        let (nfa, states) = IndexedNFA::new(fa);
+        let state_count = states.len();
+        let closures: Vec<BitSet> = nfa
+            .closures
+            .iter()
+            .map(|closure| {
+                let mut set = BitSet::new(state_count);
+                for state in closure {
+                    set.insert(*state);
+                }
+                set
+            })
+            .collect();
+        let mut accepted_states = BitSet::new(state_count);
+        for (state, accepted) in nfa.accepted.iter().enumerate() {
+            if *accepted {
+                accepted_states.insert(state);
+            }
+        }
+        Self {
+            start_states: closures[nfa.start_state].clone(),
+            accepted_states,
+            closures,
+            nfa,
+        }
+    }
+
+    pub fn accept<S>(&self, content: S) -> bool
+    where
+        S: Iterator<Item = T>,
+    {
+        let mut cur_states = self.start_states.clone();
+        let mut next_states = BitSet::new(self.closures.len());
+        for alphabet in content {
+            next_states.clear();
+            for state in cur_states.iter() {
+                if let Some(to_states) = self.nfa.transitions[state].get(&alphabet) {
+                    for to_state in to_states {
+                        next_states.union_with(&self.closures[*to_state]);
+                    }
+                }
+            }
+            if next_states.is_empty() {
+                return false;
+            }
+            std::mem::swap(&mut cur_states, &mut next_states);
+        }
+        cur_states.intersects(&self.accepted_states)
+    }
+}
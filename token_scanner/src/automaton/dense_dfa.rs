@@ -0,0 +1,313 @@
+use crate::formats::binary::{
+    encoded_len, fnv1a, push_u32, read_u32, BinaryFormatError, BinarySymbol, Header, MAGIC, VERSION,
+};
+use crate::{FiniteAutomaton, StateIdentifier, DFA};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Read-only sequence of `u32` words backing a `DenseDFA`.
+pub trait Words {
+    fn word(&self, idx: usize) -> u32;
+
+    fn word_count(&self) -> usize;
+}
+
+impl Words for Vec<u32> {
+    #[inline]
+    fn word(&self, idx: usize) -> u32 {
+        self[idx]
+    }
+
+    fn word_count(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Little-endian `u32` words read in place from a byte buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct LeWords<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Words for LeWords<'a> {
+    #[inline]
+    fn word(&self, idx: usize) -> u32 {
+        read_u32(self.bytes, idx * 4)
+    }
+
+    fn word_count(&self) -> usize {
+        self.bytes.len() / 4
+    }
+}
+
+/// A DFA with states `0..n` and a single transition table indexed by
+/// `state * class_count + class`, where symbols with the same transitions share a class, see
+/// `AlphabetClasses`. A sorted symbol table maps symbols to their classes. Built from a `DFA` with
+/// `from_dfa`, or loaded from the binary format with `DenseDFARef::from_bytes` without copying the
+/// tables.
+///
+/// ```
+/// use token_scanner::automaton::dense_dfa::{DenseDFA, DenseDFARef};
+/// use token_scanner::pre_defined_fa;
+///
+/// let dense = DenseDFA::from_dfa(&pre_defined_fa::bin_str_div_by_3());
+/// assert!(dense.accept("110".chars()));
+///
+/// let bytes = dense.to_bytes();
+/// let loaded = DenseDFARef::<char>::from_bytes(&bytes).unwrap();
+/// assert!(!loaded.accept("111".chars()));
+/// ```
+pub struct DenseDFA<T, S = Vec<u32>, A = Vec<u8>>
+where
+    T: BinarySymbol,
+    S: Words,
+    A: AsRef<[u8]>,
+{
+    state_count: usize,
+    alphabet_len: usize,
+    class_count: usize,
+    start_state: u32,
+    /// Sorted symbols.
+    symbols: S,
+    /// The class of each symbol in `symbols`.
+    symbol_classes: S,
+    transitions: S,
+    /// One bit per state.
+    accepting: A,
+    phantom: PhantomData<T>,
+}
+
+/// A `DenseDFA` borrowing its tables from a byte buffer in the binary format.
+pub type DenseDFARef<'a, T> = DenseDFA<T, LeWords<'a>, &'a [u8]>;
+
+impl<T> DenseDFA<T>
+where
+    T: BinarySymbol,
+{
+    /// Builds a dense DFA from `dfa`, with states numbered as in `DFA::relabeled` and classes as in
+    /// `AlphabetClasses`.
+    pub fn from_dfa<U: StateIdentifier>(dfa: &DFA<T, U>) -> Self {
+        let dfa = dfa.relabeled();
+        let classes = dfa.alphabet_classes();
+        let mut alphabets: Vec<&T> = dfa.alphabets().iter().collect();
+        alphabets.sort_by_key(|alphabet| alphabet.to_u32());
+        let state_count = dfa.states().len();
+        let mut transitions = Vec::with_capacity(state_count * classes.len());
+        for state in 0..state_count {
+            let map = dfa.transition_map().get(&state).unwrap();
+            transitions.extend(
+                (0..classes.len())
+                    .map(|class| *map.get(classes.representative(class)).unwrap() as u32),
+            );
+        }
+        let mut accepting = vec![0u8; state_count.div_ceil(8)];
+        for state in dfa.accepted_states() {
+            accepting[state / 8] |= 1 << (state % 8);
+        }
+        Self {
+            state_count,
+            alphabet_len: alphabets.len(),
+            class_count: classes.len(),
+            start_state: dfa.start_state() as u32,
+            symbols: alphabets.iter().map(|alphabet| alphabet.to_u32()).collect(),
+            symbol_classes: alphabets
+                .iter()
+                .map(|alphabet| classes.class(alphabet).unwrap() as u32)
+                .collect(),
+            transitions,
+            accepting,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> DenseDFARef<'a, T>
+where
+    T: BinarySymbol,
+{
+    /// Validates `bytes` written by `to_bytes` or `DFA::to_bytes` and reads the DFA in place,
+    /// without allocating.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, BinaryFormatError> {
+        let header = Header::parse::<T>(bytes)?;
+        let symbols = LeWords {
+            bytes: &bytes[header.symbols_offset()..header.classes_offset()],
+        };
+        let symbol_classes = LeWords {
+            bytes: &bytes[header.classes_offset()..header.table_offset()],
+        };
+        let transitions = LeWords {
+            bytes: &bytes[header.table_offset()..header.bitmap_offset()],
+        };
+        for idx in 0..header.symbol_count {
+            let val = symbols.word(idx);
+            if idx > 0 && symbols.word(idx - 1) >= val {
+                return Err(BinaryFormatError::UnsortedSymbols);
+            }
+            if T::from_u32(val).is_none() {
+                return Err(BinaryFormatError::InvalidSymbol(val));
+            }
+            let class = symbol_classes.word(idx);
+            if class as usize >= header.class_count {
+                return Err(BinaryFormatError::InvalidClass(class));
+            }
+        }
+        for idx in 0..transitions.word_count() {
+            let to_state = transitions.word(idx);
+            if to_state as usize >= header.state_count {
+                return Err(BinaryFormatError::InvalidState(to_state));
+            }
+        }
+        Ok(Self {
+            state_count: header.state_count,
+            alphabet_len: header.symbol_count,
+            class_count: header.class_count,
+            start_state: header.start_state as u32,
+            symbols,
+            symbol_classes,
+            transitions,
+            accepting: &bytes[header.bitmap_offset()..header.checksum_offset()],
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T, S, A> DenseDFA<T, S, A>
+where
+    T: BinarySymbol,
+    S: Words,
+    A: AsRef<[u8]>,
+{
+    pub fn state_count(&self) -> usize {
+        self.state_count
+    }
+
+    /// Number of symbols in the alphabet.
+    pub fn alphabet_len(&self) -> usize {
+        self.alphabet_len
+    }
+
+    /// Number of symbol classes, the width of the transition table.
+    pub fn class_count(&self) -> usize {
+        self.class_count
+    }
+
+    pub fn start_state(&self) -> u32 {
+        self.start_state
+    }
+
+    pub fn is_accepted(&self, state: u32) -> bool {
+        let state = state as usize;
+        self.accepting.as_ref()[state / 8] & (1 << (state % 8)) != 0
+    }
+
+    /// Returns the class of `symbol`, or `None` if it is not in the alphabet.
+    #[inline]
+    pub fn class(&self, symbol: &T) -> Option<usize> {
+        let val = symbol.to_u32();
+        let (mut low, mut high) = (0, self.alphabet_len);
+        while low < high {
+            let mid = (low + high) / 2;
+            let cur = self.symbols.word(mid);
+            if cur == val {
+                return Some(self.symbol_classes.word(mid) as usize);
+            } else if cur < val {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        None
+    }
+
+    #[inline]
+    pub fn next_state_by_class(&self, state: u32, class: usize) -> u32 {
+        self.transitions
+            .word(state as usize * self.class_count + class)
+    }
+
+    /// Returns the state after reading `symbol`, or `None` if it is not in the alphabet.
+    #[inline]
+    pub fn next_state(&self, state: u32, symbol: &T) -> Option<u32> {
+        self.class(symbol)
+            .map(|class| self.next_state_by_class(state, class))
+    }
+
+    /// Runs the DFA on `content`, rejecting it as soon as a symbol is not in the alphabet.
+    pub fn accept<I>(&self, content: I) -> bool
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut state = self.start_state;
+        for symbol in content {
+            match self.next_state(state, &symbol) {
+                None => return false,
+                Some(next_state) => state = next_state,
+            }
+        }
+        self.is_accepted(state)
+    }
+
+    /// Serializes the DFA into the binary format, see the `formats::binary` module for the layout.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(encoded_len(
+            self.state_count,
+            self.alphabet_len,
+            self.class_count,
+        ));
+        res.extend_from_slice(MAGIC);
+        res.extend_from_slice(&VERSION.to_le_bytes());
+        res.push(T::KIND);
+        res.push(0);
+        push_u32(&mut res, self.state_count);
+        push_u32(&mut res, self.alphabet_len);
+        push_u32(&mut res, self.class_count);
+        push_u32(&mut res, self.start_state as usize);
+        for words in [&self.symbols, &self.symbol_classes, &self.transitions] {
+            for idx in 0..words.word_count() {
+                res.extend_from_slice(&words.word(idx).to_le_bytes());
+            }
+        }
+        res.extend_from_slice(self.accepting.as_ref());
+        let checksum = fnv1a(&res);
+        res.extend_from_slice(&checksum.to_le_bytes());
+        res
+    }
+
+    /// Converts back into a hash map based `DFA`, with the same state numbers.
+    pub fn to_dfa(&self) -> DFA<T, usize> {
+        let alphabets: Vec<(T, usize)> = (0..self.alphabet_len)
+            .map(|idx| {
+                (
+                    T::from_u32(self.symbols.word(idx)).unwrap(),
+                    self.symbol_classes.word(idx) as usize,
+                )
+            })
+            .collect();
+        let transition_map: HashMap<usize, HashMap<T, usize>> = (0..self.state_count)
+            .map(|state| {
+                let map = alphabets
+                    .iter()
+                    .map(|(alphabet, class)| {
+                        (
+                            alphabet.clone(),
+                            self.next_state_by_class(state as u32, *class) as usize,
+                        )
+                    })
+                    .collect();
+                (state, map)
+            })
+            .collect();
+        DFA::from_formal(
+            (0..self.state_count).collect(),
+            alphabets
+                .into_iter()
+                .map(|(alphabet, _)| alphabet)
+                .collect(),
+            self.start_state as usize,
+            (0..self.state_count)
+                .filter(|state| self.is_accepted(*state as u32))
+                .collect(),
+            transition_map,
+        )
+    }
+}
@@ -1,11 +1,13 @@
-use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::automaton::error::InvalidAutomatonError;
+use crate::custom_traits::alphabet::{split_alphabets, NoneEmptyAlphabet};
 use crate::{Alphabet, CommunicativeHashSet, DFATransitionMap, FiniteAutomaton, StateIdentifier};
-use maplit::hashset;
+use maplit::{hashmap, hashset};
 use std::collections::hash_map::RandomState;
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 use std::mem::swap;
 
+#[derive(Clone, Debug)]
 pub struct DFA<T, U>
 where
     T: NoneEmptyAlphabet,
@@ -39,12 +41,11 @@ where
     }
 
     fn transition(&self, state: U, alphabet: Alphabet<T>) -> HashSet<U> {
-        if alphabet.is_none() {
-            HashSet::new()
-        } else {
-            hashset! {
-            self.transition_map.get(&state).unwrap().get(&alphabet.unwrap()).unwrap().clone()
-            }
+        match alphabet {
+            None => HashSet::new(),
+            Some(alphabet) => hashset! {
+                self.transition_map.get(&state).unwrap().get(&alphabet).unwrap().clone()
+            },
         }
     }
 
@@ -91,6 +92,11 @@ where
     T: NoneEmptyAlphabet,
     U: StateIdentifier,
 {
+    /// Initializes the DFA from its formal definition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the formal definition is invalid, see `try_from_formal`.
     pub fn from_formal(
         states: HashSet<U>,
         alphabets: HashSet<T>,
@@ -98,44 +104,130 @@ where
         accepted_states: HashSet<U>,
         transition_map: DFATransitionMap<T, U>,
     ) -> Self {
+        match Self::try_from_formal(
+            states,
+            alphabets,
+            start_state,
+            accepted_states,
+            transition_map,
+        ) {
+            Ok(res) => res,
+            Err(err) => panic!("Cannot initialize DFA with {}.", err),
+        }
+    }
+
+    /// Initializes the DFA from its formal definition, returning an error instead of panicking if
+    /// the definition is invalid. Overlapping alphabets are split into disjoint ones, see
+    /// `NoneEmptyAlphabet::split_overlapping`, and must lead to the same state wherever they
+    /// overlap.
+    pub fn try_from_formal(
+        states: HashSet<U>,
+        alphabets: HashSet<T>,
+        start_state: U,
+        accepted_states: HashSet<U>,
+        transition_map: DFATransitionMap<T, U>,
+    ) -> Result<Self, InvalidAutomatonError> {
+        let (alphabets, transition_map) = match split_alphabets(
+            &alphabets,
+            transition_map.values().flat_map(|map| map.keys()),
+        ) {
+            None => (alphabets, transition_map),
+            Some((alphabets, pieces)) => {
+                let mut split_map: DFATransitionMap<T, U> = HashMap::new();
+                for (state, map) in transition_map {
+                    let mut split_alphabet_map = HashMap::new();
+                    for (alphabet, to_state) in map {
+                        for piece in pieces[&alphabet].iter() {
+                            match split_alphabet_map.insert(piece.clone(), to_state.clone()) {
+                                Some(other) if other != to_state => {
+                                    return Err(InvalidAutomatonError::OverlappingAlphabets {
+                                        alphabets: format!("{:?}", piece),
+                                        state: state.to_string(),
+                                    })
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    split_map.insert(state, split_alphabet_map);
+                }
+                (alphabets, split_map)
+            }
+        };
         if !accepted_states.is_subset(&states) {
-            panic!(
-                "Cannot initialize NFA with accepted states {:?} not in all states {:?}.",
-                accepted_states.difference(&states),
-                states
-            )
+            return Err(InvalidAutomatonError::AcceptedStatesNotInStates {
+                accepted_states: format!("{:?}", accepted_states.difference(&states)),
+                states: format!("{:?}", states),
+            });
         }
         if !states.contains(&start_state) {
-            panic!(
-                "Cannot initialize NFA with start state {} not in all states {:?}.",
-                start_state, states
-            )
+            return Err(InvalidAutomatonError::StartStateNotInStates {
+                start_state: start_state.to_string(),
+                states: format!("{:?}", states),
+            });
         }
-        if states.len() != transition_map.len() {
-            panic!(
-                "Missing transition in transition map for states {:?}.",
-                states.difference(&HashSet::from_iter(
-                    transition_map.keys().map(|ele| { ele.clone() })
-                ))
-            )
+        let missing_states: HashSet<&U> = states
+            .iter()
+            .filter(|state| !transition_map.contains_key(state))
+            .collect();
+        if !missing_states.is_empty() {
+            return Err(InvalidAutomatonError::MissingStateTransitions {
+                states: format!("{:?}", missing_states),
+            });
         }
         for (from_state, map) in transition_map.iter() {
-            if map.len() != alphabets.len() {
-                panic!(
-                    "Missing transition in transition map for alphabets {:?} from state {}.",
-                    alphabets
-                        .difference(&HashSet::from_iter(map.keys().map(|ele| { ele.clone() }))),
-                    from_state
-                )
+            let missing_alphabets: HashSet<&T> = alphabets
+                .iter()
+                .filter(|alphabet| !map.contains_key(alphabet))
+                .collect();
+            if !missing_alphabets.is_empty() || map.len() != alphabets.len() {
+                return Err(InvalidAutomatonError::MissingAlphabetTransitions {
+                    alphabets: format!("{:?}", missing_alphabets),
+                    state: from_state.to_string(),
+                });
             }
         }
-        Self {
+        Ok(Self {
             states,
             alphabets,
             start_state,
             accepted_states,
             transition_map,
+        })
+    }
+
+    /// Like `try_from_formal`, but also rejects transitions from or to states that are not in
+    /// `states`. The importers read definitions from untrusted input and go through this check.
+    pub(crate) fn try_from_formal_strict(
+        states: HashSet<U>,
+        alphabets: HashSet<T>,
+        start_state: U,
+        accepted_states: HashSet<U>,
+        transition_map: DFATransitionMap<T, U>,
+    ) -> Result<Self, InvalidAutomatonError> {
+        let fa = Self::try_from_formal(
+            states,
+            alphabets,
+            start_state,
+            accepted_states,
+            transition_map,
+        )?;
+        let unknown_states: HashSet<&U> = fa
+            .transition_map
+            .iter()
+            .flat_map(|(from_state, map)| std::iter::once(from_state).chain(map.values()))
+            .filter(|state| !fa.states.contains(state))
+            .collect();
+        if !unknown_states.is_empty() {
+            return Err(InvalidAutomatonError::UnknownTransitionStates {
+                states: format!("{:?}", unknown_states),
+            });
         }
+        Ok(fa)
+    }
+
+    pub fn transition_map(&self) -> &DFATransitionMap<T, U> {
+        &self.transition_map
     }
 
     pub fn from_map(
@@ -161,17 +253,103 @@ where
         )
     }
 
+    /// Renames the states to `0..n`, with the start state as `0` and the remaining states in
+    /// breadth-first order, visiting alphabets in the order of their labels. Unreachable states
+    /// come last, ordered by their labels.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton};
+    ///
+    /// let dfa = pre_defined_fa::bin_str_div_by_3().relabeled();
+    /// assert_eq!(dfa.start_state(), 0);
+    /// assert!(dfa.accept("110".chars()));
+    /// ```
+    pub fn relabeled(&self) -> DFA<T, usize> {
+        let order = self.relabeled_order();
+        let old_state_to_new_state: HashMap<&U, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(new_state, old_state)| (old_state, new_state))
+            .collect();
+
+        let transition_map: DFATransitionMap<T, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(new_state, old_state)| {
+                let map = self
+                    .transition_map
+                    .get(old_state)
+                    .unwrap()
+                    .iter()
+                    .map(|(alphabet, to_state)| {
+                        (
+                            alphabet.clone(),
+                            *old_state_to_new_state.get(to_state).unwrap(),
+                        )
+                    })
+                    .collect();
+                (new_state, map)
+            })
+            .collect();
+        DFA::from_formal(
+            (0..order.len()).collect(),
+            self.alphabets.clone(),
+            0,
+            self.accepted_states
+                .iter()
+                .map(|state| *old_state_to_new_state.get(state).unwrap())
+                .collect(),
+            transition_map,
+        )
+    }
+
+    /// The states in the order `relabeled` numbers them.
+    pub(crate) fn relabeled_order(&self) -> Vec<U> {
+        let mut alphabets: Vec<&T> = self.alphabets.iter().collect();
+        alphabets.sort_by_cached_key(|alphabet| (alphabet.to_string(), format!("{:?}", alphabet)));
+        let mut order = vec![self.start_state.clone()];
+        let mut old_state_to_new_state = hashmap! {self.start_state.clone() => 0};
+        let mut idx = 0;
+        while idx < order.len() {
+            let map = self.transition_map.get(&order[idx]).unwrap();
+            for alphabet in alphabets.iter() {
+                let next_state = map.get(alphabet).unwrap();
+                if !old_state_to_new_state.contains_key(next_state) {
+                    old_state_to_new_state.insert(next_state.clone(), order.len());
+                    order.push(next_state.clone());
+                }
+            }
+            idx += 1;
+        }
+        let mut unreachable: Vec<&U> = self
+            .states
+            .iter()
+            .filter(|state| !old_state_to_new_state.contains_key(state))
+            .collect();
+        unreachable.sort_by_cached_key(|state| (state.to_string(), format!("{:?}", state)));
+        order.extend(unreachable.into_iter().cloned());
+        order
+    }
+
     pub fn minimized(&self) -> DFA<T, CommunicativeHashSet<U>> {
-        let non_final_states: HashSet<U> = HashSet::from_iter(
-            self.states
-                .difference(self.accepted_states())
-                .map(|ele| ele.clone()),
-        );
-        let mut last_equivalence = HashSet::new();
-        let mut cur_equivalence = hashset! {
+        let non_final_states: HashSet<U> =
+            HashSet::from_iter(self.states.difference(self.accepted_states()).cloned());
+        self.minimized_from(hashset! {
             CommunicativeHashSet::from(non_final_states),
             CommunicativeHashSet::from(self.accepted_states.clone()),
-        };
+        })
+    }
+
+    /// Merges equivalent states, refining `partition`, so states in different sets of the
+    /// partition are never merged.
+    pub(crate) fn minimized_from(
+        &self,
+        partition: HashSet<CommunicativeHashSet<U>>,
+    ) -> DFA<T, CommunicativeHashSet<U>> {
+        let mut last_equivalence = HashSet::new();
+        let mut cur_equivalence: HashSet<CommunicativeHashSet<U>> = partition
+            .into_iter()
+            .filter(|set| !set.is_empty())
+            .collect();
         while last_equivalence != cur_equivalence {
             swap(&mut last_equivalence, &mut cur_equivalence);
             cur_equivalence.clear();
@@ -182,7 +360,7 @@ where
                     continue;
                 }
                 for s1 in cur_last_equiv_set.hashset.iter() {
-                    if cur_new_sets.len() <= 0 {
+                    if cur_new_sets.is_empty() {
                         cur_new_sets.push(CommunicativeHashSet::from(hashset! {s1.clone()}));
                         continue;
                     }
@@ -0,0 +1,88 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result};
+
+/// Reasons why a formal definition `(Q, Σ, δ, q0, F)` does not describe a valid finite automaton.
+/// States and alphabets are kept in their debug representation, so the error type does not depend
+/// on the type parameters of the automaton.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvalidAutomatonError {
+    AcceptedStatesNotInStates {
+        accepted_states: String,
+        states: String,
+    },
+    StartStateNotInStates {
+        start_state: String,
+        states: String,
+    },
+    MissingStateTransitions {
+        states: String,
+    },
+    MissingAlphabetTransitions {
+        alphabets: String,
+        state: String,
+    },
+    UnknownTransitionStates {
+        states: String,
+    },
+    OverlappingAlphabets {
+        alphabets: String,
+        state: String,
+    },
+    TaggedStatesMismatch {
+        states: String,
+    },
+}
+
+impl Display for InvalidAutomatonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            InvalidAutomatonError::AcceptedStatesNotInStates {
+                accepted_states,
+                states,
+            } => write!(
+                f,
+                "accepted states {} not in all states {}",
+                accepted_states, states
+            ),
+            InvalidAutomatonError::StartStateNotInStates {
+                start_state,
+                states,
+            } => write!(
+                f,
+                "start state {} not in all states {}",
+                start_state, states
+            ),
+            InvalidAutomatonError::MissingStateTransitions { states } => {
+                write!(
+                    f,
+                    "missing transition in transition map for states {}",
+                    states
+                )
+            }
+            InvalidAutomatonError::MissingAlphabetTransitions { alphabets, state } => write!(
+                f,
+                "missing transition in transition map for alphabets {} from state {}",
+                alphabets, state
+            ),
+            InvalidAutomatonError::UnknownTransitionStates { states } => {
+                write!(
+                    f,
+                    "transitions from or to states {} not in all states",
+                    states
+                )
+            }
+            InvalidAutomatonError::OverlappingAlphabets { alphabets, state } => write!(
+                f,
+                "overlapping alphabets {} lead to different states from state {}",
+                alphabets, state
+            ),
+            InvalidAutomatonError::TaggedStatesMismatch { states } => write!(
+                f,
+                "states {} are either accepted without a tag or tagged without being accepted",
+                states
+            ),
+        }
+    }
+}
+
+impl Error for InvalidAutomatonError {}
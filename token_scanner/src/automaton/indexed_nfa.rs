@@ -0,0 +1,74 @@
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{FiniteAutomaton, StateIdentifier};
+use std::collections::HashMap;
+
+/// A finite automaton with states interned as `0..n` and epsilon closures computed once, shared
+/// by the simulation engines.
+pub(crate) struct IndexedNFA<T>
+where
+    T: NoneEmptyAlphabet,
+{
+    pub(crate) start_state: usize,
+    pub(crate) accepted: Vec<bool>,
+    /// Epsilon closure of every state, sorted.
+    pub(crate) closures: Vec<Vec<usize>>,
+    /// Transitions of every state on every alphabet, without epsilon closure.
+    pub(crate) transitions: Vec<HashMap<T, Vec<usize>>>,
+}
+
+impl<T> IndexedNFA<T>
+where
+    T: NoneEmptyAlphabet,
+{
+    /// Interns the states of `fa` ordered by their labels, returning the states by index.
+    pub(crate) fn new<U, F>(fa: &F) -> (Self, Vec<U>)
+    where
+        U: StateIdentifier,
+        F: FiniteAutomaton<T, U> + ?Sized,
+    {
+        let mut states: Vec<U> = fa.states().iter().cloned().collect();
+        states.sort_by_cached_key(|state| (state.to_string(), format!("{:?}", state)));
+        let state_to_idx: HashMap<&U, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (state, idx))
+            .collect();
+        let to_indices = |to_states: &mut dyn Iterator<Item = U>| -> Vec<usize> {
+            let mut res: Vec<usize> = to_states
+                .map(|state| *state_to_idx.get(&state).unwrap())
+                .collect();
+            res.sort_unstable();
+            res
+        };
+        let closures = states
+            .iter()
+            .map(|state| to_indices(&mut fa.epsilon_closure_states(state.clone()).into_iter()))
+            .collect();
+        let transitions = states
+            .iter()
+            .map(|state| {
+                fa.alphabets()
+                    .iter()
+                    .filter_map(|alphabet| {
+                        let to_states = fa.transition(state.clone(), Some(alphabet.clone()));
+                        if to_states.is_empty() {
+                            None
+                        } else {
+                            Some((alphabet.clone(), to_indices(&mut to_states.into_iter())))
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let res = Self {
+            start_state: *state_to_idx.get(&fa.start_state()).unwrap(),
+            accepted: states
+                .iter()
+                .map(|state| fa.accepted_states().contains(state))
+                .collect(),
+            closures,
+            transitions,
+        };
+        (res, states)
+    }
+}
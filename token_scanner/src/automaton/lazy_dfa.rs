@@ -0,0 +1,221 @@
+use crate::automaton::indexed_nfa::IndexedNFA;
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{FiniteAutomaton, StateIdentifier};
+use std::collections::HashMap;
+use std::mem::size_of;
+
+/// Approximate bookkeeping cost of a cached state besides its NFA states and transitions.
+const STATE_OVERHEAD: usize = 64;
+
+/// Options controlling the state cache of a `LazyDFA`.
+#[derive(Clone, Debug)]
+pub struct LazyDFAOptions {
+    /// Approximate memory budget of the state cache in bytes. The cache is cleared when adding a
+    /// state would exceed it.
+    pub cache_capacity: usize,
+    /// Number of times the cache may be cleared during a single run before the rest of the input
+    /// is simulated on the NFA without caching.
+    pub max_cache_clears: usize,
+}
+
+impl Default for LazyDFAOptions {
+    fn default() -> Self {
+        Self {
+            cache_capacity: 1 << 21,
+            max_cache_clears: 8,
+        }
+    }
+}
+
+/// Counters over all runs of a `LazyDFA`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LazyDFAStats {
+    pub cache_clears: usize,
+    /// Number of runs that fell back to NFA simulation.
+    pub nfa_fallbacks: usize,
+}
+
+/// Runs a finite automaton as a DFA whose subset states are only computed when a run reaches them,
+/// unlike `to_dfa`, which computes every reachable subset up front. Computed states and
+/// transitions are cached across runs.
+///
+/// ```
+/// use maplit::{hashmap, hashset};
+/// use token_scanner::automaton::lazy_dfa::LazyDFA;
+/// use token_scanner::NFA;
+///
+/// // Strings whose second to last character is 'a'.
+/// let nfa = NFA::from_map(
+///     0,
+///     hashset! {2},
+///     hashmap! {
+///         0 => hashmap! {Some('a') => hashset! {0, 1}, Some('b') => hashset! {0}},
+///         1 => hashmap! {Some('a') => hashset! {2}, Some('b') => hashset! {2}},
+///     },
+/// );
+/// let mut lazy = LazyDFA::new(&nfa);
+/// assert!(lazy.accept("bbab".chars()));
+/// assert!(!lazy.accept("abb".chars()));
+/// ```
+pub struct LazyDFA<T>
+where
+    T: NoneEmptyAlphabet,
+{
+    nfa: IndexedNFA<T>,
+    options: LazyDFAOptions,
+    /// Sorted NFA states of every cached state.
+    subsets: Vec<Vec<usize>>,
+    subset_to_state: HashMap<Vec<usize>, usize>,
+    transitions: Vec<HashMap<T, usize>>,
+    accepting: Vec<bool>,
+    memory_usage: usize,
+    stats: LazyDFAStats,
+}
+
+impl<T> LazyDFA<T>
+where
+    T: NoneEmptyAlphabet,
+{
+    pub fn new<U, F>(fa: &F) -> Self
+    where
+        U: StateIdentifier,
+        F: FiniteAutomaton<T, U> + ?Sized,
+    {
+        Self::with_options(fa, LazyDFAOptions::default())
+    }
+
+    pub fn with_options<U, F>(fa: &F, options: LazyDFAOptions) -> Self
+    where
+        U: StateIdentifier,
+        F: FiniteAutomaton<T, U> + ?Sized,
+    {
+        let (nfa, _) = IndexedNFA::new(fa);
+        Self {
+            nfa,
+            options,
+            subsets: vec![],
+            subset_to_state: HashMap::new(),
+            transitions: vec![],
+            accepting: vec![],
+            memory_usage: 0,
+            stats: LazyDFAStats::default(),
+        }
+    }
+
+    /// Number of states currently in the cache.
+    pub fn cached_states(&self) -> usize {
+        self.subsets.len()
+    }
+
+    /// Approximate memory used by the cache in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
+    pub fn stats(&self) -> LazyDFAStats {
+        self.stats
+    }
+
+    pub fn accept<S>(&mut self, content: S) -> bool
+    where
+        S: Iterator<Item = T>,
+    {
+        let mut content = content;
+        let mut cache_clears = 0;
+        let start_subset = self.nfa.closures[self.nfa.start_state].clone();
+        let mut cur_state = match self.subset_to_state.get(&start_subset) {
+            Some(state) => *state,
+            None => {
+                if self.memory_usage + self.state_cost(&start_subset) > self.options.cache_capacity
+                {
+                    self.clear_cache();
+                }
+                self.add_state(start_subset)
+            }
+        };
+        while let Some(alphabet) = content.next() {
+            if let Some(next_state) = self.transitions[cur_state].get(&alphabet) {
+                cur_state = *next_state;
+                continue;
+            }
+            let next_subset = self.step(&self.subsets[cur_state], &alphabet);
+            let next_state = self.subset_to_state.get(&next_subset).cloned();
+            let cost = self.transition_cost()
+                + match next_state {
+                    None => self.state_cost(&next_subset),
+                    Some(_) => 0,
+                };
+            if self.memory_usage + cost > self.options.cache_capacity {
+                cache_clears += 1;
+                self.stats.cache_clears += 1;
+                if cache_clears > self.options.max_cache_clears {
+                    self.stats.nfa_fallbacks += 1;
+                    return self.simulate(next_subset, content);
+                }
+                let cur_subset = self.subsets[cur_state].clone();
+                self.clear_cache();
+                cur_state = self.add_state(cur_subset);
+            }
+            let next_state = match self.subset_to_state.get(&next_subset) {
+                Some(state) => *state,
+                None => self.add_state(next_subset),
+            };
+            self.transitions[cur_state].insert(alphabet, next_state);
+            self.memory_usage += self.transition_cost();
+            cur_state = next_state;
+        }
+        self.accepting[cur_state]
+    }
+
+    /// Runs the rest of `content` on sets of NFA states, without touching the cache.
+    fn simulate<S>(&self, subset: Vec<usize>, content: S) -> bool
+    where
+        S: Iterator<Item = T>,
+    {
+        let mut cur_subset = subset;
+        for alphabet in content {
+            cur_subset = self.step(&cur_subset, &alphabet);
+        }
+        cur_subset.iter().any(|state| self.nfa.accepted[*state])
+    }
+
+    fn step(&self, subset: &[usize], alphabet: &T) -> Vec<usize> {
+        let mut res: Vec<usize> = subset
+            .iter()
+            .filter_map(|state| self.nfa.transitions[*state].get(alphabet))
+            .flatten()
+            .flat_map(|state| self.nfa.closures[*state].iter().cloned())
+            .collect();
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    fn add_state(&mut self, subset: Vec<usize>) -> usize {
+        let state = self.subsets.len();
+        self.memory_usage += self.state_cost(&subset);
+        self.accepting
+            .push(subset.iter().any(|nfa_state| self.nfa.accepted[*nfa_state]));
+        self.transitions.push(HashMap::new());
+        self.subset_to_state.insert(subset.clone(), state);
+        self.subsets.push(subset);
+        state
+    }
+
+    fn clear_cache(&mut self) {
+        self.subsets.clear();
+        self.subset_to_state.clear();
+        self.transitions.clear();
+        self.accepting.clear();
+        self.memory_usage = 0;
+    }
+
+    /// The subset is stored twice, once as the state and once as the key of `subset_to_state`.
+    fn state_cost(&self, subset: &[usize]) -> usize {
+        STATE_OVERHEAD + 2 * subset.len() * size_of::<usize>()
+    }
+
+    fn transition_cost(&self) -> usize {
+        size_of::<T>() + size_of::<usize>()
+    }
+}
@@ -1,2 +1,12 @@
+pub mod alphabet_classes;
+pub mod bitset_nfa;
+pub mod dense_dfa;
 pub mod dfa;
+pub mod error;
+mod indexed_nfa;
+pub mod lazy_dfa;
 pub mod nfa;
+#[cfg(feature = "rayon")]
+pub(crate) mod parallel;
+pub mod search;
+pub mod tagged;
@@ -1,9 +1,11 @@
-use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::automaton::error::InvalidAutomatonError;
+use crate::custom_traits::alphabet::{split_alphabets, NoneEmptyAlphabet};
 use crate::{Alphabet, FiniteAutomaton, NFATransitionMap, StateIdentifier};
 use maplit::hashset;
 use std::collections::hash_map::RandomState;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+#[derive(Clone, Debug)]
 pub struct NFA<T, U>
 where
     T: NoneEmptyAlphabet,
@@ -53,6 +55,11 @@ where
     T: NoneEmptyAlphabet,
     U: StateIdentifier,
 {
+    /// Initializes the NFA from its formal definition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the formal definition is invalid, see `try_from_formal`.
     pub fn from_formal(
         states: HashSet<U>,
         alphabets: HashSet<T>,
@@ -60,26 +67,106 @@ where
         accepted_states: HashSet<U>,
         transition_map: NFATransitionMap<T, U>,
     ) -> Self {
+        match Self::try_from_formal(
+            states,
+            alphabets,
+            start_state,
+            accepted_states,
+            transition_map,
+        ) {
+            Ok(res) => res,
+            Err(err) => panic!("Cannot initialize NFA with {}.", err),
+        }
+    }
+
+    /// Initializes the NFA from its formal definition, returning an error instead of panicking if
+    /// the definition is invalid. Overlapping alphabets are split into disjoint ones, see
+    /// `NoneEmptyAlphabet::split_overlapping`.
+    pub fn try_from_formal(
+        states: HashSet<U>,
+        alphabets: HashSet<T>,
+        start_state: U,
+        accepted_states: HashSet<U>,
+        transition_map: NFATransitionMap<T, U>,
+    ) -> Result<Self, InvalidAutomatonError> {
+        let used = transition_map.values().flat_map(|map| map.keys().flatten());
+        let (alphabets, transition_map) = match split_alphabets(&alphabets, used) {
+            None => (alphabets, transition_map),
+            Some((alphabets, pieces)) => {
+                let mut split_map: NFATransitionMap<T, U> = HashMap::new();
+                for (state, map) in transition_map {
+                    let split_alphabet_map = split_map.entry(state).or_default();
+                    for (alphabet, to_states) in map {
+                        let split_alphabets: Vec<Alphabet<T>> = match alphabet {
+                            None => vec![None],
+                            Some(alphabet) => pieces[&alphabet].iter().cloned().map(Some).collect(),
+                        };
+                        for alphabet in split_alphabets {
+                            split_alphabet_map
+                                .entry(alphabet)
+                                .or_default()
+                                .extend(to_states.iter().cloned());
+                        }
+                    }
+                }
+                (alphabets, split_map)
+            }
+        };
         if !accepted_states.is_subset(&states) {
-            panic!(
-                "Cannot initialize NFA with accepted states {:?} not in all states {:?}.",
-                accepted_states.difference(&states),
-                states
-            )
+            return Err(InvalidAutomatonError::AcceptedStatesNotInStates {
+                accepted_states: format!("{:?}", accepted_states.difference(&states)),
+                states: format!("{:?}", states),
+            });
         }
         if !states.contains(&start_state) {
-            panic!(
-                "Cannot initialize NFA with start state {} not in all states {:?}.",
-                start_state, states
-            )
+            return Err(InvalidAutomatonError::StartStateNotInStates {
+                start_state: start_state.to_string(),
+                states: format!("{:?}", states),
+            });
         }
-        Self {
+        Ok(Self {
             states,
             alphabets,
             start_state,
             accepted_states,
             transition_map,
+        })
+    }
+
+    /// Like `try_from_formal`, but also rejects transitions from or to states that are not in
+    /// `states`. The importers read definitions from untrusted input and go through this check.
+    pub(crate) fn try_from_formal_strict(
+        states: HashSet<U>,
+        alphabets: HashSet<T>,
+        start_state: U,
+        accepted_states: HashSet<U>,
+        transition_map: NFATransitionMap<T, U>,
+    ) -> Result<Self, InvalidAutomatonError> {
+        let fa = Self::try_from_formal(
+            states,
+            alphabets,
+            start_state,
+            accepted_states,
+            transition_map,
+        )?;
+        let unknown_states: HashSet<&U> = fa
+            .transition_map
+            .iter()
+            .flat_map(|(from_state, map)| {
+                std::iter::once(from_state).chain(map.values().flat_map(|states| states.iter()))
+            })
+            .filter(|state| !fa.states.contains(state))
+            .collect();
+        if !unknown_states.is_empty() {
+            return Err(InvalidAutomatonError::UnknownTransitionStates {
+                states: format!("{:?}", unknown_states),
+            });
         }
+        Ok(fa)
+    }
+
+    pub fn transition_map(&self) -> &NFATransitionMap<T, U> {
+        &self.transition_map
     }
 
     pub fn from_map(
@@ -0,0 +1,122 @@
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{CommunicativeHashSet, DFATransitionMap, FiniteAutomaton, StateIdentifier, DFA};
+use rayon::prelude::*;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::BuildHasher;
+use std::sync::Mutex;
+
+const SHARD_COUNT: usize = 64;
+
+/// Set of discovered subset states, split into shards locked independently so threads rarely
+/// wait on each other.
+struct StateTable<U>
+where
+    U: StateIdentifier,
+{
+    hash_builder: RandomState,
+    shards: Vec<Mutex<HashSet<CommunicativeHashSet<U>>>>,
+}
+
+impl<U> StateTable<U>
+where
+    U: StateIdentifier,
+{
+    fn new() -> Self {
+        Self {
+            hash_builder: RandomState::new(),
+            shards: (0..SHARD_COUNT)
+                .map(|_| Mutex::new(HashSet::new()))
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if `state` was not in the table yet.
+    fn insert(&self, state: &CommunicativeHashSet<U>) -> bool {
+        let shard = self.hash_builder.hash_one(state) as usize % SHARD_COUNT;
+        self.shards[shard].lock().unwrap().insert(state.clone())
+    }
+
+    fn into_states(self) -> HashSet<CommunicativeHashSet<U>> {
+        self.shards
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap())
+            .collect()
+    }
+}
+
+/// Subset construction processing the frontier of newly discovered subset states in parallel.
+pub(crate) fn to_dfa_parallel<T, U, F>(fa: &F) -> DFA<T, CommunicativeHashSet<U>>
+where
+    T: NoneEmptyAlphabet + Send + Sync,
+    U: StateIdentifier + Send + Sync,
+    F: FiniteAutomaton<T, U> + Sync + ?Sized,
+{
+    let alphabets: Vec<&T> = fa.alphabets().iter().collect();
+    let nfa_states: Vec<&U> = fa.states().iter().collect();
+    let closure_transitions: HashMap<&U, HashMap<&T, HashSet<U>>> = nfa_states
+        .par_iter()
+        .map(|state| {
+            let map = alphabets
+                .iter()
+                .map(|alphabet| {
+                    (
+                        *alphabet,
+                        fa.epsilon_closure_transition((*state).clone(), (*alphabet).clone()),
+                    )
+                })
+                .collect();
+            (*state, map)
+        })
+        .collect();
+
+    let start_state = CommunicativeHashSet::from(fa.epsilon_closure_states(fa.start_state()));
+    let table = StateTable::new();
+    table.insert(&start_state);
+    let mut frontier = vec![start_state.clone()];
+    let mut transition_map: DFATransitionMap<T, CommunicativeHashSet<U>> = HashMap::new();
+    while !frontier.is_empty() {
+        let rows: Vec<_> = frontier
+            .into_par_iter()
+            .map(|from_state| {
+                let map: HashMap<T, CommunicativeHashSet<U>> = alphabets
+                    .iter()
+                    .map(|alphabet| {
+                        let mut to_states = HashSet::new();
+                        for state in from_state.hashset.iter() {
+                            to_states.extend(closure_transitions[state][alphabet].iter().cloned());
+                        }
+                        ((*alphabet).clone(), CommunicativeHashSet::from(to_states))
+                    })
+                    .collect();
+                (from_state, map)
+            })
+            .collect();
+        frontier = rows
+            .par_iter()
+            .flat_map_iter(|(_, map)| map.values())
+            .filter(|to_state| table.insert(to_state))
+            .cloned()
+            .collect();
+        transition_map.extend(rows);
+    }
+
+    let states = table.into_states();
+    let accepted_states = states
+        .iter()
+        .filter(|state| {
+            state
+                .hashset
+                .iter()
+                .any(|nfa_state| fa.accepted_states().contains(nfa_state))
+        })
+        .cloned()
+        .collect();
+    DFA::from_formal(
+        states,
+        fa.alphabets().clone(),
+        start_state,
+        accepted_states,
+        transition_map,
+    )
+}
@@ -0,0 +1,178 @@
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::custom_traits::finite_automaton::co_reachable_states;
+use crate::{FiniteAutomaton, StateIdentifier};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::marker::PhantomData;
+
+/// Runs `fa` as if its language was prefixed by `Σ*`, where every state remembers the position
+/// it started reading at.
+pub(crate) struct Searcher<'a, T, U, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    fa: &'a F,
+    /// States from which an accepted state can be reached, all others are dropped.
+    live_states: HashSet<U>,
+    start_states: Vec<U>,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T, U, F> Searcher<'a, T, U, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    pub(crate) fn new(fa: &'a F) -> Self {
+        let live_states = co_reachable_states(fa);
+        let start_states = fa
+            .epsilon_closure_states(fa.start_state())
+            .into_iter()
+            .filter(|state| live_states.contains(state))
+            .collect();
+        Self {
+            fa,
+            live_states,
+            start_states,
+            phantom: PhantomData,
+        }
+    }
+
+    fn next_states(&self, state: &U, alphabet: &T) -> Vec<U> {
+        if !self.fa.alphabets().contains(alphabet) {
+            return vec![];
+        }
+        self.fa
+            .epsilon_closure_transition(state.clone(), alphabet.clone())
+            .into_iter()
+            .filter(|state| self.live_states.contains(state))
+            .collect()
+    }
+
+    /// Returns the leftmost match starting at or after `from`, and the longest among the matches
+    /// starting there.
+    pub(crate) fn find_at(&self, content: &[T], from: usize) -> Option<(usize, usize)> {
+        // The earliest start of the runs in every state, later runs in the same state are
+        // redundant.
+        let mut runs: HashMap<U, usize> = HashMap::new();
+        let mut res: Option<(usize, usize)> = None;
+        for pos in from..=content.len() {
+            if res.is_none() {
+                for state in self.start_states.iter() {
+                    runs.entry(state.clone()).or_insert(pos);
+                }
+            }
+            for (state, start) in runs.iter() {
+                if !self.fa.accepted_states().contains(state) {
+                    continue;
+                }
+                res = match res {
+                    Some((res_start, _)) if res_start < *start => res,
+                    _ => Some((*start, pos)),
+                };
+            }
+            if let Some((res_start, _)) = res {
+                runs.retain(|_, start| *start <= res_start);
+                if runs.is_empty() {
+                    break;
+                }
+            }
+            if pos == content.len() {
+                break;
+            }
+            let mut next_runs: HashMap<U, usize> = HashMap::new();
+            for (state, start) in runs.iter() {
+                for next_state in self.next_states(state, &content[pos]) {
+                    let next_start = next_runs.entry(next_state).or_insert(*start);
+                    *next_start = (*next_start).min(*start);
+                }
+            }
+            runs = next_runs;
+        }
+        res
+    }
+
+    /// Returns every match, ordered by end and then by start.
+    pub(crate) fn find_overlapping(&self, content: &[T]) -> Vec<(usize, usize)> {
+        let mut res = vec![];
+        let mut runs: HashSet<(U, usize)> = HashSet::new();
+        for pos in 0..=content.len() {
+            runs.extend(self.start_states.iter().map(|state| (state.clone(), pos)));
+            let starts: BTreeSet<usize> = runs
+                .iter()
+                .filter(|(state, _)| self.fa.accepted_states().contains(state))
+                .map(|(_, start)| *start)
+                .collect();
+            res.extend(starts.into_iter().map(|start| (start, pos)));
+            if pos == content.len() {
+                break;
+            }
+            runs = runs
+                .iter()
+                .flat_map(|(state, start)| {
+                    self.next_states(state, &content[pos])
+                        .into_iter()
+                        .map(move |next_state| (next_state, *start))
+                })
+                .collect();
+        }
+        res
+    }
+}
+
+/// Iterator over the non-overlapping matches of an automaton in a text, returned by
+/// `FiniteAutomaton::find_iter`.
+pub struct FindIter<'a, T, U, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    searcher: Searcher<'a, T, U, F>,
+    content: Vec<T>,
+    pos: usize,
+}
+
+impl<'a, T, U, F> FindIter<'a, T, U, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    pub(crate) fn new(fa: &'a F, content: Vec<T>) -> Self {
+        Self {
+            searcher: Searcher::new(fa),
+            content,
+            pos: 0,
+        }
+    }
+}
+
+impl<'a, T, U, F> Iterator for FindIter<'a, T, U, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos > self.content.len() {
+            return None;
+        }
+        match self.searcher.find_at(&self.content, self.pos) {
+            None => {
+                self.pos = self.content.len() + 1;
+                None
+            }
+            Some((start, end)) => {
+                // An empty match is followed by a search one position further, so the iterator
+                // always makes progress.
+                self.pos = if start == end { end + 1 } else { end };
+                Some((start, end))
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::automaton::error::InvalidAutomatonError;
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{CommunicativeHashSet, FiniteAutomaton, StateIdentifier, DFA, NFA};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::marker::PhantomData;
+
+/// A finite automaton whose accepted states carry a tag, such as the token kind or the rule
+/// matched by the state. Smaller tags have a higher priority: a set of states reached together
+/// reports the smallest of their tags.
+///
+/// ```
+/// use maplit::{hashmap, hashset};
+/// use token_scanner::automaton::tagged::TaggedNFA;
+/// use token_scanner::NFA;
+///
+/// // "if" is tagged 0 and "i" followed by any number of "f" is tagged 1.
+/// let nfa = NFA::from_map(
+///     0,
+///     hashset! {2, 4},
+///     hashmap! {
+///         0 => hashmap! {None => hashset! {1, 3}},
+///         1 => hashmap! {Some('i') => hashset! {5}},
+///         5 => hashmap! {Some('f') => hashset! {2}},
+///         3 => hashmap! {Some('i') => hashset! {4}},
+///         4 => hashmap! {Some('f') => hashset! {4}},
+///     },
+/// );
+/// let tagged = TaggedNFA::new(nfa, hashmap! {2 => 0, 4 => 1}).unwrap();
+/// let dfa = tagged.to_dfa().minimized();
+/// assert_eq!(dfa.accept_tag("if".chars()), Some(&0));
+/// assert_eq!(dfa.accept_tag("iff".chars()), Some(&1));
+/// assert_eq!(dfa.accept_tag("f".chars()), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TaggedAutomaton<T, U, L, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U>,
+{
+    automaton: F,
+    tags: HashMap<U, L>,
+    phantom: PhantomData<T>,
+}
+
+pub type TaggedNFA<T, U, L> = TaggedAutomaton<T, U, L, NFA<T, U>>;
+
+pub type TaggedDFA<T, U, L> = TaggedAutomaton<T, U, L, DFA<T, U>>;
+
+impl<T, U, L, F> TaggedAutomaton<T, U, L, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    L: Clone + Ord,
+    F: FiniteAutomaton<T, U>,
+{
+    /// Tags the accepted states of `automaton`. Every accepted state must have a tag, and only
+    /// accepted states may have one.
+    pub fn new(automaton: F, tags: HashMap<U, L>) -> Result<Self, InvalidAutomatonError> {
+        let mut mismatched: Vec<String> = automaton
+            .accepted_states()
+            .iter()
+            .filter(|state| !tags.contains_key(state))
+            .chain(
+                tags.keys()
+                    .filter(|state| !automaton.accepted_states().contains(state)),
+            )
+            .map(|state| format!("{:?}", state))
+            .collect();
+        if !mismatched.is_empty() {
+            mismatched.sort();
+            return Err(InvalidAutomatonError::TaggedStatesMismatch {
+                states: format!("[{}]", mismatched.join(", ")),
+            });
+        }
+        Ok(Self {
+            automaton,
+            tags,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn automaton(&self) -> &F {
+        &self.automaton
+    }
+
+    pub fn tags(&self) -> &HashMap<U, L> {
+        &self.tags
+    }
+
+    pub fn tag(&self, state: &U) -> Option<&L> {
+        self.tags.get(state)
+    }
+
+    pub fn into_parts(self) -> (F, HashMap<U, L>) {
+        (self.automaton, self.tags)
+    }
+
+    /// Runs the automaton on `content`, returning the tag of the highest priority among the
+    /// states it ends in, or `None` if the content is not accepted.
+    pub fn accept_tag<S>(&self, content: S) -> Option<&L>
+    where
+        S: Iterator<Item = T>,
+    {
+        let fa = &self.automaton;
+        let mut cur_states = fa.epsilon_closure_states(fa.start_state());
+        for alphabet in content {
+            if !fa.alphabets().contains(&alphabet) {
+                return None;
+            }
+            let mut next_states = HashSet::new();
+            for state in cur_states.iter() {
+                next_states.extend(fa.epsilon_closure_transition(state.clone(), alphabet.clone()));
+            }
+            cur_states = next_states;
+        }
+        cur_states
+            .iter()
+            .filter_map(|state| self.tags.get(state))
+            .min()
+    }
+
+    /// Subset construction, where every subset gets the tag of the highest priority among its
+    /// states.
+    pub fn to_dfa(&self) -> TaggedDFA<T, CommunicativeHashSet<U>, L> {
+        let dfa = self.automaton.to_dfa();
+        let tags = dfa
+            .accepted_states()
+            .iter()
+            .map(|subset| {
+                let tag = subset
+                    .hashset
+                    .iter()
+                    .filter_map(|state| self.tags.get(state))
+                    .min()
+                    .unwrap()
+                    .clone();
+                (subset.clone(), tag)
+            })
+            .collect();
+        TaggedAutomaton {
+            automaton: dfa,
+            tags,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, U, L> TaggedDFA<T, U, L>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    L: Clone + Ord,
+{
+    /// Merges equivalent states, where states with different tags are never equivalent.
+    pub fn minimized(&self) -> TaggedDFA<T, CommunicativeHashSet<U>, L> {
+        let mut partition: BTreeMap<Option<&L>, HashSet<U>> = BTreeMap::new();
+        for state in self.automaton.states() {
+            partition
+                .entry(self.tags.get(state))
+                .or_default()
+                .insert(state.clone());
+        }
+        let dfa = self.automaton.minimized_from(
+            partition
+                .into_values()
+                .map(CommunicativeHashSet::from)
+                .collect(),
+        );
+        let tags = dfa
+            .accepted_states()
+            .iter()
+            .map(|set| {
+                let any_state = set.hashset.iter().next().unwrap();
+                (set.clone(), self.tags.get(any_state).unwrap().clone())
+            })
+            .collect();
+        TaggedAutomaton {
+            automaton: dfa,
+            tags,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Renames the states to `0..n` like `DFA::relabeled`, keeping their tags.
+    pub fn relabeled(&self) -> TaggedDFA<T, usize, L> {
+        let tags = self
+            .automaton
+            .relabeled_order()
+            .iter()
+            .enumerate()
+            .filter_map(|(new_state, old_state)| {
+                self.tags.get(old_state).map(|tag| (new_state, tag.clone()))
+            })
+            .collect();
+        TaggedAutomaton {
+            automaton: self.automaton.relabeled(),
+            tags,
+            phantom: PhantomData,
+        }
+    }
+}
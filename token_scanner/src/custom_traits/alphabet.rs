@@ -1,9 +1,43 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 
-pub trait NoneEmptyAlphabet: Eq + Hash + Clone + Display + Debug {}
+pub trait NoneEmptyAlphabet: Eq + Hash + Clone + Display + Debug {
+    /// Splits alphabets that share symbols, like overlapping character ranges, into disjoint
+    /// alphabets. Returns the pieces covering each alphabet, or `None` if the alphabets are
+    /// disjoint already. Automata split their alphabets with it when they are constructed, so
+    /// that subset construction treats every symbol correctly. Alphabets of single symbols never
+    /// overlap, which is the default.
+    fn split_overlapping(_alphabets: &HashSet<Self>) -> Option<HashMap<Self, Vec<Self>>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
 pub type Alphabet<T> = Option<T>;
 
+/// Splits `alphabets` and the alphabets `used` by transitions with
+/// `NoneEmptyAlphabet::split_overlapping`. Returns the pieces of `alphabets`, and the pieces
+/// covering every alphabet, or `None` if the alphabets are disjoint.
+#[allow(clippy::type_complexity)]
+pub(crate) fn split_alphabets<'a, T>(
+    alphabets: &HashSet<T>,
+    used: impl Iterator<Item = &'a T>,
+) -> Option<(HashSet<T>, HashMap<T, Vec<T>>)>
+where
+    T: NoneEmptyAlphabet + 'a,
+{
+    let mut all_alphabets = alphabets.clone();
+    all_alphabets.extend(used.cloned());
+    let pieces = T::split_overlapping(&all_alphabets)?;
+    let split = alphabets
+        .iter()
+        .flat_map(|alphabet| pieces.get(alphabet).unwrap().iter().cloned())
+        .collect();
+    Some((split, pieces))
+}
+
 /// Any automaton state has an identifier, which will also be used as its label. The identifier has
 /// to implement `StateIdentifier` trait. This trait is implemented for `usize`, `i8`, `i32`, `i64`,
 /// `i128`, `char`, `&str` and `String` by default.
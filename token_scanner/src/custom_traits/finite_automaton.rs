@@ -1,13 +1,19 @@
+use crate::automaton::alphabet_classes::AlphabetClasses;
+use crate::automaton::search::{FindIter, Searcher};
 use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::formats::graphviz::{Graph, GraphvizOptions};
+use crate::formats::table::TransitionTable;
 use crate::{Alphabet, StateIdentifier, DFA};
 use maplit::{hashmap, hashset};
 use std::cmp::PartialEq;
 use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter, Result};
-use std::fs::File;
+use std::fs::{create_dir_all, File};
 use std::hash::{Hash, Hasher};
+use std::io;
+use std::io::{BufWriter, Write};
 use std::iter::{FromIterator, Iterator};
-use std::vec::Vec;
+use std::path::Path;
 
 pub type NFATransitionMap<T, U> = HashMap<U, HashMap<Alphabet<T>, HashSet<U>>>;
 pub type DFATransitionMap<T, U> = HashMap<U, HashMap<T, U>>;
@@ -26,8 +32,7 @@ where
     fn epsilon_closure_states(&self, state: U) -> HashSet<U> {
         let mut result = hashset! { state.clone() };
         let mut stack = vec![state];
-        while !stack.is_empty() {
-            let cur_state = stack.pop().unwrap();
+        while let Some(cur_state) = stack.pop() {
             for neighbor in self.transition(cur_state, None) {
                 if result.contains(&neighbor) {
                     continue;
@@ -72,6 +77,134 @@ where
         false
     }
 
+    /// Returns the length of the longest prefix of `content` that is accepted, or `None` if no
+    /// prefix is, not even the empty one. Reading stops as soon as no accepted state can be
+    /// reached anymore, so the rest of `content` is not consumed.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton};
+    ///
+    /// let dfa = pre_defined_fa::bin_str_div_by_3();
+    /// assert_eq!(dfa.longest_match("1101".chars()), Some(3));
+    /// assert_eq!(dfa.longest_match("10x11".chars()), Some(0));
+    /// ```
+    fn longest_match<S>(&self, content: S) -> Option<usize>
+    where
+        S: Iterator<Item = T>,
+    {
+        self.all_match_ends(content).last().cloned()
+    }
+
+    /// Returns the lengths of all prefixes of `content` that are accepted, in increasing order.
+    /// Reading stops as soon as no accepted state can be reached anymore.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton};
+    ///
+    /// let dfa = pre_defined_fa::bin_str_div_by_3();
+    /// assert_eq!(dfa.all_match_ends("0110".chars()), vec![0, 1, 3, 4]);
+    /// ```
+    fn all_match_ends<S>(&self, content: S) -> Vec<usize>
+    where
+        S: Iterator<Item = T>,
+    {
+        let mut content = content;
+        let live_states = co_reachable_states(self);
+        let mut res = vec![];
+        let mut cur_states: HashSet<U> = self
+            .epsilon_closure_states(self.start_state())
+            .into_iter()
+            .filter(|state| live_states.contains(state))
+            .collect();
+        let mut len = 0;
+        loop {
+            if cur_states
+                .iter()
+                .any(|state| self.accepted_states().contains(state))
+            {
+                res.push(len);
+            }
+            if cur_states.is_empty() {
+                break;
+            }
+            let alphabet = match content.next() {
+                Some(alphabet) if self.alphabets().contains(&alphabet) => alphabet,
+                _ => break,
+            };
+            let mut next_states = HashSet::new();
+            for state in cur_states.iter() {
+                next_states.extend(
+                    self.epsilon_closure_transition(state.clone(), alphabet.clone())
+                        .into_iter()
+                        .filter(|state| live_states.contains(state)),
+                );
+            }
+            cur_states = next_states;
+            len += 1;
+        }
+        res
+    }
+
+    /// Returns the leftmost span `(start, end)` of `content` in the language of the automaton,
+    /// and the longest one among the spans starting there. Positions count alphabets.
+    /// ```
+    /// use maplit::{hashmap, hashset};
+    /// use token_scanner::{FiniteAutomaton, NFA};
+    ///
+    /// // "ab" or "abcd".
+    /// let nfa = NFA::from_map(
+    ///     0,
+    ///     hashset! {2, 4},
+    ///     hashmap! {
+    ///         0 => hashmap! {Some('a') => hashset! {1}},
+    ///         1 => hashmap! {Some('b') => hashset! {2}},
+    ///         2 => hashmap! {Some('c') => hashset! {3}},
+    ///         3 => hashmap! {Some('d') => hashset! {4}},
+    ///     },
+    /// );
+    /// assert_eq!(nfa.find("xxabcdab".chars()), Some((2, 6)));
+    /// assert_eq!(nfa.find("xxabc".chars()), Some((2, 4)));
+    /// assert_eq!(nfa.find("ba".chars()), None);
+    /// ```
+    fn find<S>(&self, content: S) -> Option<(usize, usize)>
+    where
+        S: Iterator<Item = T>,
+    {
+        Searcher::new(self).find_at(&content.collect::<Vec<T>>(), 0)
+    }
+
+    /// Returns the successive non-overlapping spans found by `find`, each search starting where
+    /// the previous span ended.
+    /// ```
+    /// use maplit::{hashmap, hashset};
+    /// use token_scanner::{FiniteAutomaton, NFA};
+    ///
+    /// // One or more 'a'.
+    /// let nfa = NFA::from_map(
+    ///     0,
+    ///     hashset! {1},
+    ///     hashmap! {
+    ///         0 => hashmap! {Some('a') => hashset! {1}},
+    ///         1 => hashmap! {Some('a') => hashset! {1}},
+    ///     },
+    /// );
+    /// let spans: Vec<(usize, usize)> = nfa.find_iter("baab.a".chars()).collect();
+    /// assert_eq!(spans, vec![(1, 3), (5, 6)]);
+    /// ```
+    fn find_iter<S>(&self, content: S) -> FindIter<'_, T, U, Self>
+    where
+        S: Iterator<Item = T>,
+    {
+        FindIter::new(self, content.collect())
+    }
+
+    /// Returns every span of `content` in the language of the automaton, including spans
+    /// overlapping or containing each other, ordered by end and then by start.
+    fn find_overlapping<S>(&self, content: S) -> Vec<(usize, usize)>
+    where
+        S: Iterator<Item = T>,
+    {
+        Searcher::new(self).find_overlapping(&content.collect::<Vec<T>>())
+    }
+
     fn is_deterministic(&self) -> bool {
         for state in self.states() {
             if self.epsilon_closure_states(state.clone()).len() > 1 {
@@ -181,7 +314,7 @@ where
                     }
                     false
                 })
-                .map(|ele| ele.clone()),
+                .cloned(),
         );
         let mut final_transition_map: DFATransitionMap<T, CommunicativeHashSet<U>> = HashMap::new();
         for (from_state, map) in new_transition_map.iter() {
@@ -202,104 +335,204 @@ where
         res
     }
 
-    /// Exports the finite automaton as GraphViz dot file to given path.
+    /// Same as `to_dfa`, but processes every frontier of newly discovered states in parallel on
+    /// the rayon thread pool, for automata with many states. The result is identical to `to_dfa`
+    /// after `DFA::relabeled`.
+    ///
+    /// Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    fn to_dfa_parallel(&self) -> DFA<T, CommunicativeHashSet<U>>
+    where
+        Self: Sync,
+        T: Send + Sync,
+        U: Send + Sync,
+    {
+        crate::automaton::parallel::to_dfa_parallel(self)
+    }
+
+    /// Partitions the alphabets into classes of alphabets with the same transitions from every
+    /// state, see `AlphabetClasses`.
+    fn alphabet_classes(&self) -> AlphabetClasses<T> {
+        AlphabetClasses::new(self)
+    }
+
+    /// Returns a value that displays the finite automaton as a plain-text transition table, see the
+    /// `formats::table` module for the format.
     /// ```
     /// use token_scanner::{pre_defined_fa, FiniteAutomaton};
-    /// use std::env;
     ///
     /// let dfa = pre_defined_fa::bin_str_div_by_3();
-    /// let mut tmp_dir = env::temp_dir();
-    /// tmp_dir.push("dfa.dot");
-    /// dfa.export_graphviz_dot_file(tmp_dir.to_str().unwrap().to_string());
+    /// println!("{}", dfa.transition_table());
     /// ```
-    fn export_graphviz_dot_file(&self, output_file_path: String) {
-        let mut output = File::create(output_file_path.clone()).unwrap();
-
-        let nodes: Vec<U> = Vec::from_iter(self.states().clone());
-        let mut nodes_to_idx = HashMap::new();
-        for (idx, node) in nodes.iter().enumerate() {
-            nodes_to_idx.insert(node, idx);
-        }
-
-        let mut edges = Vec::<(usize, usize, String)>::new();
-        let epsilon_utf8 = "&#949;";
-        let epsilon_string = String::from(epsilon_utf8);
-        for (i, node) in nodes.iter().enumerate() {
-            for next_state in self.epsilon_closure_states(node.clone()) {
-                if *node != next_state {
-                    edges.push((
-                        i,
-                        nodes_to_idx.get(&next_state).unwrap().clone(),
-                        epsilon_string.clone(),
-                    ));
-                }
-            }
-            for alphabet in self.alphabets() {
-                for next_state in self.transition(node.clone(), Some(alphabet.clone())) {
-                    edges.push((
-                        i,
-                        nodes_to_idx.get(&next_state).unwrap().clone(),
-                        alphabet.to_string().clone(),
-                    ));
-                }
-            }
-        }
-        let nodes_string = nodes
-            .iter()
-            .map(|node| {
-                node.to_string()
-                    + (if self.accepted_states().contains(node) {
-                        "*".to_string()
-                    } else {
-                        "".to_string()
-                    })
-                    .as_str()
-            })
-            .collect();
-        let graph = Graph {
-            nodes: nodes_string,
-            edges,
-        };
-
-        dot::render(&graph, &mut output).unwrap();
-        println!("GraphViz dot file rendered to: {}", output_file_path);
+    fn transition_table(&self) -> TransitionTable<'_, T, U, Self> {
+        TransitionTable::new(self)
     }
-}
 
-type Nd = usize;
-type Ed<'a> = &'a (usize, usize, String);
-struct Graph {
-    nodes: Vec<String>,
-    edges: Vec<(usize, usize, String)>,
-}
-
-impl<'a> dot::Labeller<'a, Nd, Ed<'a>> for Graph {
-    fn graph_id(&'a self) -> dot::Id<'a> {
-        dot::Id::new("nfa").unwrap()
+    /// Renders the finite automaton in GraphViz dot format with the default `GraphvizOptions`.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton};
+    ///
+    /// let dfa = pre_defined_fa::bin_str_div_by_3();
+    /// let dot = dfa.to_dot_string();
+    /// assert!(dot.starts_with("digraph"));
+    /// ```
+    fn to_dot_string(&self) -> String {
+        self.to_dot_string_with_options(&GraphvizOptions::default())
     }
-    fn node_id(&'a self, n: &Nd) -> dot::Id<'a> {
-        dot::Id::new(format!("STATE{}", n)).unwrap()
+
+    fn to_dot_string_with_options(&self, options: &GraphvizOptions) -> String {
+        let mut output = Vec::new();
+        self.write_dot_with_options(&mut output, options)
+            .expect("Writing to a Vec<u8> cannot fail.");
+        String::from_utf8(output).expect("GraphViz dot output is always valid UTF-8.")
     }
-    fn node_label(&self, n: &Nd) -> dot::LabelText {
-        dot::LabelText::LabelStr(("S".to_string() + self.nodes[*n].as_str()).into())
+
+    /// Writes the finite automaton in GraphViz dot format to `output`, using the default
+    /// `GraphvizOptions`.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton};
+    /// use std::io::stdout;
+    ///
+    /// let dfa = pre_defined_fa::bin_str_div_by_3();
+    /// dfa.write_dot(&mut stdout()).unwrap();
+    /// ```
+    fn write_dot<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        self.write_dot_with_options(output, &GraphvizOptions::default())
     }
-    fn edge_label<'b>(&'b self, e: &Ed) -> dot::LabelText<'b> {
-        dot::LabelText::LabelStr(e.2.clone().into())
+
+    /// Writes the finite automaton in GraphViz dot format to `output`. Accepted states are drawn
+    /// with `options.accepted_node_shape`, the start state is pointed to by an arrow from an
+    /// invisible node, and all transitions between the same pair of states are merged into one
+    /// edge.
+    /// ```
+    /// use token_scanner::formats::graphviz::{GraphvizOptions, RankDir};
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton};
+    ///
+    /// let dfa = pre_defined_fa::bin_str_div_by_3();
+    /// let options = GraphvizOptions {
+    ///     graph_name: "div_by_3".to_string(),
+    ///     rankdir: RankDir::TopToBottom,
+    ///     ..GraphvizOptions::default()
+    /// };
+    /// let mut output = Vec::new();
+    /// dfa.write_dot_with_options(&mut output, &options).unwrap();
+    /// ```
+    fn write_dot_with_options<W: Write>(
+        &self,
+        output: &mut W,
+        options: &GraphvizOptions,
+    ) -> io::Result<()> {
+        let (graph, _) = Graph::from_automaton(self);
+        graph.render(options, output)
     }
-}
 
-impl<'a> dot::GraphWalk<'a, Nd, Ed<'a>> for Graph {
-    fn nodes(&self) -> dot::Nodes<'a, Nd> {
-        (0..self.nodes.len()).collect()
+    /// Exports the finite automaton as GraphViz dot file to given path, using the default
+    /// `GraphvizOptions`.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton};
+    /// use std::env;
+    ///
+    /// let dfa = pre_defined_fa::bin_str_div_by_3();
+    /// let mut tmp_dir = env::temp_dir();
+    /// tmp_dir.push("dfa.dot");
+    /// dfa.export_graphviz_dot_file(tmp_dir.to_str().unwrap().to_string()).unwrap();
+    /// ```
+    fn export_graphviz_dot_file(&self, output_file_path: String) -> io::Result<()> {
+        self.export_graphviz_dot_file_with_options(output_file_path, &GraphvizOptions::default())
     }
-    fn edges(&'a self) -> dot::Edges<'a, Ed<'a>> {
-        self.edges.iter().collect()
+
+    fn export_graphviz_dot_file_with_options(
+        &self,
+        output_file_path: String,
+        options: &GraphvizOptions,
+    ) -> io::Result<()> {
+        let mut output = BufWriter::new(File::create(output_file_path)?);
+        self.write_dot_with_options(&mut output, options)?;
+        output.flush()
     }
-    fn source(&self, e: &Ed) -> Nd {
-        e.0.clone()
+
+    /// Simulates the finite automaton on `content` and exports one GraphViz dot file per step to
+    /// the given directory, named `frame_000.dot`, `frame_001.dot`, etc. The first frame shows the
+    /// epsilon closure of the start state before any input is consumed, every following frame
+    /// highlights the set of active states after consuming one more alphabet, together with the
+    /// transitions (including epsilon transitions) that were just taken.
+    /// ```
+    /// use token_scanner::{NFA, FiniteAutomaton};
+    /// use maplit::{hashset, hashmap};
+    /// use std::env;
+    ///
+    /// let nfa = NFA::from_map(
+    ///     0,
+    ///     hashset! {0, 2},
+    ///     hashmap! {
+    ///         0 => hashmap!{ Some('0') => hashset!{1} },
+    ///         1 => hashmap!{ Some('1') => hashset!{2}, None => hashset!{0} },
+    ///     },
+    /// );
+    /// let mut tmp_dir = env::temp_dir();
+    /// tmp_dir.push("nfa_run");
+    /// nfa.export_graphviz_run_frames("001".chars(), tmp_dir.to_str().unwrap().to_string())
+    ///     .unwrap();
+    /// ```
+    fn export_graphviz_run_frames<S>(&self, content: S, output_dir_path: String) -> io::Result<()>
+    where
+        S: Iterator<Item = T>,
+    {
+        self.export_graphviz_run_frames_with_options(
+            content,
+            output_dir_path,
+            &GraphvizOptions::default(),
+        )
     }
-    fn target(&self, e: &Ed) -> Nd {
-        e.1.clone()
+
+    /// Same as `export_graphviz_run_frames`, rendering every frame with the given options.
+    fn export_graphviz_run_frames_with_options<S>(
+        &self,
+        content: S,
+        output_dir_path: String,
+        options: &GraphvizOptions,
+    ) -> io::Result<()>
+    where
+        S: Iterator<Item = T>,
+    {
+        let mut cur_states = self.epsilon_closure_states(self.start_state());
+        let mut frames = vec![(cur_states.clone(), HashSet::new())];
+        for alphabet in content {
+            let mut next_states = HashSet::new();
+            let mut taken_transitions = HashSet::new();
+            for state in cur_states.iter() {
+                for next_state in self.transition(state.clone(), Some(alphabet.clone())) {
+                    taken_transitions.insert((
+                        state.clone(),
+                        next_state.clone(),
+                        Some(alphabet.clone()),
+                    ));
+                    for closure_state in self.epsilon_closure_states(next_state.clone()) {
+                        if closure_state != next_state {
+                            taken_transitions.insert((
+                                next_state.clone(),
+                                closure_state.clone(),
+                                None,
+                            ));
+                        }
+                        next_states.insert(closure_state);
+                    }
+                }
+            }
+            frames.push((next_states.clone(), taken_transitions));
+            cur_states = next_states;
+        }
+
+        create_dir_all(&output_dir_path)?;
+        let (mut graph, nodes_to_idx) = Graph::from_automaton(self);
+        for (idx, (active_states, taken_transitions)) in frames.iter().enumerate() {
+            graph.highlight(&nodes_to_idx, active_states, taken_transitions);
+            let frame_path = Path::new(&output_dir_path).join(format!("frame_{:03}.dot", idx));
+            let mut output = BufWriter::new(File::create(frame_path)?);
+            graph.render(options, &mut output)?;
+            output.flush()?;
+        }
+        Ok(())
     }
 }
 
@@ -330,6 +563,10 @@ where
         self.hashset.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.hashset.is_empty()
+    }
+
     pub fn contains(&self, val: &T) -> bool {
         self.hashset.contains(val)
     }
@@ -381,10 +618,42 @@ where
     T: Eq + Hash + Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", self.to_string())
+        write!(f, "{}", self)
     }
 }
 
 impl<T> Eq for CommunicativeHashSet<T> where T: Eq + Hash {}
 
 impl<T> StateIdentifier for CommunicativeHashSet<T> where T: StateIdentifier {}
+
+/// Returns the states from which an accepted state can be reached.
+pub(crate) fn co_reachable_states<T, U, F>(fa: &F) -> HashSet<U>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    let mut predecessors: HashMap<U, HashSet<U>> = HashMap::new();
+    for state in fa.states() {
+        let mut alphabets: Vec<Alphabet<T>> = fa.alphabets().iter().cloned().map(Some).collect();
+        alphabets.push(None);
+        for alphabet in alphabets {
+            for to_state in fa.transition(state.clone(), alphabet) {
+                predecessors
+                    .entry(to_state)
+                    .or_default()
+                    .insert(state.clone());
+            }
+        }
+    }
+    let mut res = fa.accepted_states().clone();
+    let mut stack: Vec<U> = res.iter().cloned().collect();
+    while let Some(state) = stack.pop() {
+        for predecessor in predecessors.get(&state).into_iter().flatten() {
+            if res.insert(predecessor.clone()) {
+                stack.push(predecessor.clone());
+            }
+        }
+    }
+    res
+}
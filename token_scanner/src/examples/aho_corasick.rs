@@ -0,0 +1,189 @@
+use crate::unicode::char_class::{CharClass, CharRange};
+use crate::{DFATransitionMap, FiniteAutomaton, DFA};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Aho–Corasick automaton of a list of keywords: a DFA over the characters of the keywords whose
+/// states are the prefixes of the keywords, with the failure links resolved into transitions, so
+/// reading a text reaches the longest keyword prefix that is a suffix of the text read so far.
+///
+/// ```
+/// use token_scanner::examples::aho_corasick::AhoCorasick;
+///
+/// let ac = AhoCorasick::new(&["he", "she", "his", "hers"]);
+/// let ends = ac.ends_at("ushers");
+/// assert_eq!(ends[4], vec![0, 1]); // "she" and "he" end after "ushe".
+/// assert_eq!(ends[6], vec![3]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AhoCorasick {
+    keywords: Vec<String>,
+    dfa: DFA<char, usize>,
+    /// Keywords ending in every state, sorted.
+    outputs: Vec<Vec<usize>>,
+}
+
+impl AhoCorasick {
+    pub fn new<S: AsRef<str>>(keywords: &[S]) -> Self {
+        let keywords: Vec<String> = keywords
+            .iter()
+            .map(|keyword| keyword.as_ref().to_string())
+            .collect();
+        let mut goto: Vec<HashMap<char, usize>> = vec![HashMap::new()];
+        let mut outputs: Vec<Vec<usize>> = vec![vec![]];
+        for (idx, keyword) in keywords.iter().enumerate() {
+            let mut state = 0;
+            for c in keyword.chars() {
+                state = match goto[state].get(&c) {
+                    Some(next_state) => *next_state,
+                    None => {
+                        let next_state = goto.len();
+                        goto.push(HashMap::new());
+                        outputs.push(vec![]);
+                        goto[state].insert(c, next_state);
+                        next_state
+                    }
+                };
+            }
+            outputs[state].push(idx);
+        }
+        let alphabets: HashSet<char> = keywords
+            .iter()
+            .flat_map(|keyword| keyword.chars())
+            .collect();
+
+        // States are visited by increasing depth, so the failure state of every state, which is
+        // shallower, already has its transitions.
+        let mut transition_map: Vec<HashMap<char, usize>> = vec![HashMap::new(); goto.len()];
+        let mut fail = vec![0; goto.len()];
+        let mut queue = VecDeque::new();
+        for c in alphabets.iter() {
+            let next_state = goto[0].get(c).cloned().unwrap_or(0);
+            transition_map[0].insert(*c, next_state);
+            if next_state != 0 {
+                queue.push_back(next_state);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            let fail_outputs = outputs[fail[state]].clone();
+            outputs[state].extend(fail_outputs);
+            outputs[state].sort_unstable();
+            outputs[state].dedup();
+            for c in alphabets.iter() {
+                let fail_next_state = transition_map[fail[state]][c];
+                match goto[state].get(c) {
+                    Some(next_state) => {
+                        fail[*next_state] = fail_next_state;
+                        transition_map[state].insert(*c, *next_state);
+                        queue.push_back(*next_state);
+                    }
+                    None => {
+                        transition_map[state].insert(*c, fail_next_state);
+                    }
+                }
+            }
+        }
+
+        let accepted_states = (0..goto.len())
+            .filter(|state| !outputs[*state].is_empty())
+            .collect();
+        let dfa = DFA::from_formal(
+            (0..goto.len()).collect(),
+            alphabets,
+            0,
+            accepted_states,
+            transition_map.into_iter().enumerate().collect(),
+        );
+        Self {
+            keywords,
+            dfa,
+            outputs,
+        }
+    }
+
+    pub fn keywords(&self) -> &[String] {
+        &self.keywords
+    }
+
+    /// The automaton, which accepts the texts ending with a keyword. Its alphabets are the
+    /// characters of the keywords only.
+    pub fn dfa(&self) -> &DFA<char, usize> {
+        &self.dfa
+    }
+
+    pub fn into_dfa(self) -> DFA<char, usize> {
+        self.dfa
+    }
+
+    /// The automaton over classes of characters, with one more class for the characters that
+    /// appear in no keyword, which go back to the start state. It reads any text.
+    /// ```
+    /// use token_scanner::examples::aho_corasick::AhoCorasick;
+    /// use token_scanner::unicode::char_automaton::CharAutomaton;
+    ///
+    /// let dfa = AhoCorasick::new(&["if"]).to_char_class_dfa();
+    /// assert!(dfa.accept_chars("x = 1; if".chars()));
+    /// assert!(!dfa.accept_chars("ifx".chars()));
+    /// ```
+    pub fn to_char_class_dfa(&self) -> DFA<CharClass, usize> {
+        let other: CharClass = self
+            .dfa
+            .alphabets()
+            .iter()
+            .map(|c| CharRange::single(*c))
+            .collect::<CharClass>()
+            .negated();
+        let mut alphabets: HashSet<CharClass> = self
+            .dfa
+            .alphabets()
+            .iter()
+            .map(|c| CharClass::from(*c))
+            .collect();
+        let mut transition_map: DFATransitionMap<CharClass, usize> = self
+            .dfa
+            .transition_map()
+            .iter()
+            .map(|(state, map)| {
+                let map = map
+                    .iter()
+                    .map(|(c, to_state)| (CharClass::from(*c), *to_state))
+                    .collect();
+                (*state, map)
+            })
+            .collect();
+        if !other.is_empty() {
+            for map in transition_map.values_mut() {
+                map.insert(other.clone(), 0);
+            }
+            alphabets.insert(other);
+        }
+        DFA::from_formal(
+            self.dfa.states().clone(),
+            alphabets,
+            self.dfa.start_state(),
+            self.dfa.accepted_states().clone(),
+            transition_map,
+        )
+    }
+
+    /// Returns the indices of the keywords ending in `state`.
+    pub fn outputs(&self, state: usize) -> &[usize] {
+        &self.outputs[state]
+    }
+
+    /// Returns the indices of the keywords ending after every prefix of `text`, by number of
+    /// characters read, from the empty prefix to the whole text. Characters that appear in no
+    /// keyword go back to the start state.
+    pub fn ends_at(&self, text: &str) -> Vec<Vec<usize>> {
+        let mut state = 0;
+        let mut res = vec![self.outputs[state].clone()];
+        for c in text.chars() {
+            state = if self.dfa.alphabets().contains(&c) {
+                self.dfa.transition_map()[&state][&c]
+            } else {
+                0
+            };
+            res.push(self.outputs[state].clone());
+        }
+        res
+    }
+}
@@ -1 +1,2 @@
+pub mod aho_corasick;
 pub mod pre_defined_fa;
@@ -1,4 +1,7 @@
+#[allow(clippy::module_inception)]
 pub mod pre_defined_fa {
+    use crate::examples::aho_corasick::AhoCorasick;
+    use crate::unicode::char_class::CharClass;
     use crate::DFA;
     use maplit::{hashmap, hashset};
 
@@ -39,4 +42,20 @@ pub mod pre_defined_fa {
             },
         )
     }
+
+    /// This function returns the Aho–Corasick automaton of `keywords`, which accepts the strings
+    /// ending with one of the keywords. Its alphabets are classes of characters, one of them for
+    /// the characters of no keyword, see `AhoCorasick::to_char_class_dfa`. Use `AhoCorasick` to
+    /// know which keywords end in a state.
+    /// ```
+    /// use token_scanner::pre_defined_fa::keywords;
+    /// use token_scanner::unicode::char_automaton::CharAutomaton;
+    /// let dfa = keywords(&["if", "else"]);
+    /// assert!(dfa.accept_chars("eelse".chars()));
+    /// assert!(dfa.accept_chars("x = 1; else".chars()));
+    /// assert!(!dfa.accept_chars("ifel".chars()));
+    /// ```
+    pub fn keywords<S: AsRef<str>>(keywords: &[S]) -> DFA<CharClass, usize> {
+        AhoCorasick::new(keywords).to_char_class_dfa()
+    }
 }
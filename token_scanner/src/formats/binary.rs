@@ -0,0 +1,299 @@
+//! A compact binary format for DFAs, loaded without going through hash maps of hash maps.
+//!
+//! All integers are little-endian:
+//!
+//! | Field              | Size                          |
+//! |--------------------|-------------------------------|
+//! | Magic `TSDF`       | 4 bytes                       |
+//! | Version            | `u16`                         |
+//! | Symbol kind        | `u8`, see `BinarySymbol::KIND` |
+//! | Reserved           | `u8`, always 0                |
+//! | State count        | `u32`                         |
+//! | Symbol count       | `u32`                         |
+//! | Class count        | `u32`                         |
+//! | Start state        | `u32`                         |
+//! | Symbols            | symbol count `u32`s, ascending |
+//! | Symbol classes     | symbol count `u32`s, the class of each symbol |
+//! | Transition table   | state count × class count `u32`s, row by row |
+//! | Accepting bitmap   | one bit per state, rounded up to bytes |
+//! | Checksum           | `u32`, FNV-1a of everything before it |
+//!
+//! States are numbered as in `DFA::relabeled`, and symbols with identical transitions share a
+//! class, see `AlphabetClasses`. `DenseDFA` runs the format in place.
+use crate::automaton::dense_dfa::{DenseDFA, DenseDFARef};
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{InvalidAutomatonError, StateIdentifier, DFA};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+pub(crate) const MAGIC: &[u8; 4] = b"TSDF";
+pub(crate) const VERSION: u16 = 2;
+pub(crate) const HEADER_LEN: usize = 24;
+const CHECKSUM_LEN: usize = 4;
+
+/// Alphabets that can be stored in the binary format as a `u32`.
+pub trait BinarySymbol: NoneEmptyAlphabet {
+    /// Identifies the symbol type in the header, so bytes written for one type are not loaded as
+    /// another.
+    const KIND: u8;
+
+    fn to_u32(&self) -> u32;
+
+    fn from_u32(val: u32) -> Option<Self>;
+}
+
+impl BinarySymbol for char {
+    const KIND: u8 = 1;
+
+    fn to_u32(&self) -> u32 {
+        *self as u32
+    }
+
+    fn from_u32(val: u32) -> Option<Self> {
+        std::char::from_u32(val)
+    }
+}
+
+impl BinarySymbol for u8 {
+    const KIND: u8 = 2;
+
+    fn to_u32(&self) -> u32 {
+        u32::from(*self)
+    }
+
+    fn from_u32(val: u32) -> Option<Self> {
+        u8::try_from(val).ok()
+    }
+}
+
+impl BinarySymbol for u32 {
+    const KIND: u8 = 3;
+
+    fn to_u32(&self) -> u32 {
+        *self
+    }
+
+    fn from_u32(val: u32) -> Option<Self> {
+        Some(val)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BinaryFormatError {
+    UnexpectedEnd { expected: usize, found: usize },
+    TrailingBytes { expected: usize, found: usize },
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    SymbolKindMismatch { expected: u8, found: u8 },
+    ChecksumMismatch { expected: u32, found: u32 },
+    InvalidSymbol(u32),
+    UnsortedSymbols,
+    InvalidClass(u32),
+    InvalidState(u32),
+    InvalidAutomaton(InvalidAutomatonError),
+}
+
+impl Display for BinaryFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryFormatError::UnexpectedEnd { expected, found } => write!(
+                f,
+                "unexpected end of input, expected {} bytes but found {}",
+                expected, found
+            ),
+            BinaryFormatError::TrailingBytes { expected, found } => write!(
+                f,
+                "trailing bytes after DFA, expected {} bytes but found {}",
+                expected, found
+            ),
+            BinaryFormatError::InvalidMagic => write!(f, "input is not a binary DFA"),
+            BinaryFormatError::UnsupportedVersion(version) => {
+                write!(f, "unsupported binary DFA version {}", version)
+            }
+            BinaryFormatError::SymbolKindMismatch { expected, found } => write!(
+                f,
+                "symbol kind mismatch, expected {} but found {}",
+                expected, found
+            ),
+            BinaryFormatError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "checksum mismatch, expected {:#010x} but found {:#010x}",
+                expected, found
+            ),
+            BinaryFormatError::InvalidSymbol(symbol) => write!(f, "invalid symbol {}", symbol),
+            BinaryFormatError::UnsortedSymbols => {
+                write!(f, "symbols are not in strictly ascending order")
+            }
+            BinaryFormatError::InvalidClass(class) => write!(f, "invalid class {}", class),
+            BinaryFormatError::InvalidState(state) => write!(f, "invalid state {}", state),
+            BinaryFormatError::InvalidAutomaton(err) => write!(f, "invalid automaton: {}", err),
+        }
+    }
+}
+
+impl Error for BinaryFormatError {}
+
+impl From<InvalidAutomatonError> for BinaryFormatError {
+    fn from(err: InvalidAutomatonError) -> Self {
+        BinaryFormatError::InvalidAutomaton(err)
+    }
+}
+
+impl<T, U> DFA<T, U>
+where
+    T: BinarySymbol,
+    U: StateIdentifier,
+{
+    /// Serializes the DFA into the binary format, see the `formats::binary` module for the layout.
+    /// States are renamed as in `relabeled`.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton, DFA};
+    ///
+    /// let bytes = pre_defined_fa::bin_str_div_by_3().to_bytes();
+    /// let dfa: DFA<char, usize> = DFA::from_bytes(&bytes).unwrap();
+    /// assert!(dfa.accept("110".chars()));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        DenseDFA::from_dfa(self).to_bytes()
+    }
+}
+
+impl<T> DFA<T, usize>
+where
+    T: BinarySymbol,
+{
+    /// Loads a DFA written by `to_bytes`, checking the header, the checksum and the transition
+    /// table before building it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BinaryFormatError> {
+        Ok(DenseDFARef::<T>::from_bytes(bytes)?.to_dfa())
+    }
+}
+
+/// The fixed-size header, validated against the input length and checksum.
+pub(crate) struct Header {
+    pub(crate) state_count: usize,
+    pub(crate) symbol_count: usize,
+    pub(crate) class_count: usize,
+    pub(crate) start_state: usize,
+}
+
+impl Header {
+    pub(crate) fn parse<T: BinarySymbol>(bytes: &[u8]) -> Result<Self, BinaryFormatError> {
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err(BinaryFormatError::UnexpectedEnd {
+                expected: HEADER_LEN + CHECKSUM_LEN,
+                found: bytes.len(),
+            });
+        }
+        if &bytes[..4] != MAGIC {
+            return Err(BinaryFormatError::InvalidMagic);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(BinaryFormatError::UnsupportedVersion(version));
+        }
+        if bytes[6] != T::KIND {
+            return Err(BinaryFormatError::SymbolKindMismatch {
+                expected: T::KIND,
+                found: bytes[6],
+            });
+        }
+        let header = Header {
+            state_count: read_u32(bytes, 8) as usize,
+            symbol_count: read_u32(bytes, 12) as usize,
+            class_count: read_u32(bytes, 16) as usize,
+            start_state: read_u32(bytes, 20) as usize,
+        };
+        let expected =
+            checked_encoded_len(header.state_count, header.symbol_count, header.class_count)
+                .ok_or(BinaryFormatError::UnexpectedEnd {
+                    expected: usize::MAX,
+                    found: bytes.len(),
+                })?;
+        if bytes.len() < expected {
+            return Err(BinaryFormatError::UnexpectedEnd {
+                expected,
+                found: bytes.len(),
+            });
+        }
+        if bytes.len() > expected {
+            return Err(BinaryFormatError::TrailingBytes {
+                expected,
+                found: bytes.len(),
+            });
+        }
+        let checksum_offset = header.checksum_offset();
+        let expected_checksum = read_u32(bytes, checksum_offset);
+        let checksum = fnv1a(&bytes[..checksum_offset]);
+        if checksum != expected_checksum {
+            return Err(BinaryFormatError::ChecksumMismatch {
+                expected: expected_checksum,
+                found: checksum,
+            });
+        }
+        if header.start_state >= header.state_count {
+            return Err(BinaryFormatError::InvalidState(header.start_state as u32));
+        }
+        Ok(header)
+    }
+
+    pub(crate) fn symbols_offset(&self) -> usize {
+        HEADER_LEN
+    }
+
+    pub(crate) fn classes_offset(&self) -> usize {
+        self.symbols_offset() + self.symbol_count * 4
+    }
+
+    pub(crate) fn table_offset(&self) -> usize {
+        self.classes_offset() + self.symbol_count * 4
+    }
+
+    pub(crate) fn bitmap_offset(&self) -> usize {
+        self.table_offset() + self.state_count * self.class_count * 4
+    }
+
+    pub(crate) fn checksum_offset(&self) -> usize {
+        self.bitmap_offset() + self.state_count.div_ceil(8)
+    }
+}
+
+pub(crate) fn encoded_len(state_count: usize, symbol_count: usize, class_count: usize) -> usize {
+    checked_encoded_len(state_count, symbol_count, class_count).unwrap()
+}
+
+fn checked_encoded_len(
+    state_count: usize,
+    symbol_count: usize,
+    class_count: usize,
+) -> Option<usize> {
+    let symbols = symbol_count.checked_mul(8)?;
+    let table = state_count.checked_mul(class_count)?.checked_mul(4)?;
+    let bitmap = state_count.div_ceil(8);
+    HEADER_LEN
+        .checked_add(symbols)?
+        .checked_add(table)?
+        .checked_add(bitmap)?
+        .checked_add(CHECKSUM_LEN)
+}
+
+pub(crate) fn push_u32(bytes: &mut Vec<u8>, val: usize) {
+    let val = u32::try_from(val).expect("DFA is too large for the binary format.");
+    bytes.extend_from_slice(&val.to_le_bytes());
+}
+
+pub(crate) fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+pub(crate) fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193)
+    })
+}
@@ -0,0 +1,643 @@
+use crate::formats::graphviz::EPSILON_LABEL;
+use crate::{InvalidAutomatonError, DFA, NFA};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+const EPSILON_CHAR: &str = "ε";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GraphvizParseError {
+    /// The input is not valid in the supported subset of the DOT language.
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    /// The input is a valid graph, but it does not describe a finite automaton.
+    InvalidGraph(String),
+    InvalidAutomaton(InvalidAutomatonError),
+}
+
+impl Display for GraphvizParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphvizParseError::Syntax { line, message } => {
+                write!(f, "syntax error on line {}: {}", line, message)
+            }
+            GraphvizParseError::InvalidGraph(message) => write!(f, "invalid graph: {}", message),
+            GraphvizParseError::InvalidAutomaton(err) => write!(f, "invalid automaton: {}", err),
+        }
+    }
+}
+
+impl Error for GraphvizParseError {}
+
+impl From<InvalidAutomatonError> for GraphvizParseError {
+    fn from(err: InvalidAutomatonError) -> Self {
+        GraphvizParseError::InvalidAutomaton(err)
+    }
+}
+
+type Result<T> = std::result::Result<T, GraphvizParseError>;
+
+impl NFA<String, String> {
+    /// Parses a GraphViz dot file in the format written by `FiniteAutomaton::write_dot`.
+    ///
+    /// States are identified by their labels (or node IDs if they have no label), accepted
+    /// states are drawn as `doublecircle`, and the start state is the target of the only edge
+    /// coming from an invisible, `point`, `none` or `plaintext` node. Edge labels may list several
+    /// alphabets separated by `,`, ranges of characters like `a-z`, and `&#949;` or `ε` for
+    /// epsilon transitions. A backslash escapes `,`, `-` and itself, and `\u{hex}` stands for any
+    /// character, such as whitespace, which is otherwise trimmed.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton, NFA};
+    ///
+    /// let dot = pre_defined_fa::bin_str_div_by_3().to_dot_string();
+    /// let nfa = NFA::from_dot_str(&dot).unwrap();
+    /// assert!(nfa.accept("1001".chars().map(|c| c.to_string())));
+    /// ```
+    pub fn from_dot_str(dot: &str) -> Result<Self> {
+        let parsed = ParsedAutomaton::parse(dot)?;
+        let mut transition_map = HashMap::new();
+        let mut alphabets = HashSet::new();
+        for (from_state, alphabet, to_state) in parsed.transitions {
+            if let Some(val) = &alphabet {
+                alphabets.insert(val.clone());
+            }
+            transition_map
+                .entry(from_state)
+                .or_insert_with(HashMap::new)
+                .entry(alphabet)
+                .or_insert_with(HashSet::new)
+                .insert(to_state);
+        }
+        Ok(NFA::try_from_formal_strict(
+            parsed.states,
+            alphabets,
+            parsed.start_state,
+            parsed.accepted_states,
+            transition_map,
+        )?)
+    }
+}
+
+impl DFA<String, String> {
+    /// Parses a GraphViz dot file in the format written by `FiniteAutomaton::write_dot`, see
+    /// `NFA::from_dot_str`. Fails if the graph contains epsilon transitions, or if any state does
+    /// not have exactly one transition for every alphabet.
+    /// ```
+    /// use token_scanner::{pre_defined_fa, FiniteAutomaton, DFA};
+    ///
+    /// let dot = pre_defined_fa::bin_str_div_by_3().to_dot_string();
+    /// let dfa = DFA::from_dot_str(&dot).unwrap();
+    /// assert!(dfa.accept("1001".chars().map(|c| c.to_string())));
+    /// ```
+    pub fn from_dot_str(dot: &str) -> Result<Self> {
+        let parsed = ParsedAutomaton::parse(dot)?;
+        let mut transition_map: HashMap<String, HashMap<String, String>> = parsed
+            .states
+            .iter()
+            .map(|state| (state.clone(), HashMap::new()))
+            .collect();
+        let mut alphabets = HashSet::new();
+        for (from_state, alphabet, to_state) in parsed.transitions {
+            let alphabet = match alphabet {
+                None => {
+                    return Err(GraphvizParseError::InvalidGraph(format!(
+                        "epsilon transition from state {} in a DFA",
+                        from_state
+                    )))
+                }
+                Some(val) => val,
+            };
+            alphabets.insert(alphabet.clone());
+            let map = transition_map.get_mut(&from_state).unwrap();
+            match map.get(&alphabet) {
+                Some(existing) if *existing != to_state => {
+                    return Err(GraphvizParseError::InvalidGraph(format!(
+                        "state {} has more than one transition for alphabet {}",
+                        from_state, alphabet
+                    )))
+                }
+                _ => map.insert(alphabet, to_state),
+            };
+        }
+        Ok(DFA::try_from_formal_strict(
+            parsed.states,
+            alphabets,
+            parsed.start_state,
+            parsed.accepted_states,
+            transition_map,
+        )?)
+    }
+}
+
+struct ParsedAutomaton {
+    states: HashSet<String>,
+    start_state: String,
+    accepted_states: HashSet<String>,
+    transitions: Vec<(String, Option<String>, String)>,
+}
+
+impl ParsedAutomaton {
+    fn parse(dot: &str) -> Result<Self> {
+        let graph = Parser::new(tokenize(dot)?).parse_graph()?;
+
+        let is_start_marker = |node: &NodeStmt| {
+            node.attributes
+                .get("style")
+                .is_some_and(|style| style.contains("invis"))
+                || node.attributes.get("shape").is_some_and(|shape| {
+                    shape == "point" || shape == "none" || shape == "plaintext"
+                })
+        };
+        let mut id_to_state = HashMap::new();
+        let mut states = HashSet::new();
+        let mut accepted_states = HashSet::new();
+        for node in graph.nodes.iter().filter(|node| !is_start_marker(node)) {
+            let label = node
+                .attributes
+                .get("label")
+                .cloned()
+                .unwrap_or_else(|| node.id.clone());
+            if !states.insert(label.clone()) {
+                return Err(GraphvizParseError::InvalidGraph(format!(
+                    "more than one state labeled {}",
+                    label
+                )));
+            }
+            if node.attributes.get("shape").map(String::as_str) == Some("doublecircle") {
+                accepted_states.insert(label.clone());
+            }
+            id_to_state.insert(node.id.clone(), label);
+        }
+
+        let mut start_states = vec![];
+        let mut transitions = vec![];
+        for edge in graph.edges.iter() {
+            let from_state = id_to_state.get(&edge.from);
+            let to_state = match id_to_state.get(&edge.to) {
+                None => {
+                    return Err(GraphvizParseError::Syntax {
+                        line: edge.line,
+                        message: format!("edge into start marker {}", edge.to),
+                    })
+                }
+                Some(val) => val,
+            };
+            let from_state = match from_state {
+                None => {
+                    start_states.push(to_state.clone());
+                    continue;
+                }
+                Some(val) => val,
+            };
+            let label = match edge.attributes.get("label") {
+                None => {
+                    return Err(GraphvizParseError::Syntax {
+                        line: edge.line,
+                        message: format!("edge {} -> {} has no label", edge.from, edge.to),
+                    })
+                }
+                Some(val) => val,
+            };
+            for alphabet in split_edge_label(label, edge.line)? {
+                transitions.push((from_state.clone(), alphabet, to_state.clone()));
+            }
+        }
+        if start_states.len() != 1 {
+            return Err(GraphvizParseError::InvalidGraph(format!(
+                "expected exactly one start arrow, found {}",
+                start_states.len()
+            )));
+        }
+        Ok(Self {
+            states,
+            start_state: start_states.pop().unwrap(),
+            accepted_states,
+            transitions,
+        })
+    }
+}
+
+/// Splits a merged edge label such as `&#949;,0,a-c` into the alphabets it stands for, where
+/// `None` is epsilon. Escaped characters, written as `\,`, `\-`, `\\` or `\u{hex}`, neither
+/// separate alphabets nor form ranges, and are not trimmed.
+fn split_edge_label(label: &str, line: usize) -> Result<Vec<Option<String>>> {
+    if label == EPSILON_CHAR {
+        return Ok(vec![None]);
+    }
+    if label.chars().count() == 1 {
+        return Ok(vec![Some(label.to_string())]);
+    }
+    let mut res = vec![];
+    let chars = unescape_label(label, line)?;
+    for part in chars.split(|(c, escaped)| *c == ',' && !escaped) {
+        let start = part
+            .iter()
+            .position(|(c, escaped)| *escaped || !c.is_whitespace())
+            .unwrap_or(part.len());
+        let end = part
+            .iter()
+            .rposition(|(c, escaped)| *escaped || !c.is_whitespace())
+            .map_or(start, |idx| idx + 1);
+        let part = &part[start..end];
+        let text: String = part.iter().map(|(c, _)| *c).collect();
+        match part {
+            [] => {
+                return Err(GraphvizParseError::Syntax {
+                    line,
+                    message: format!("empty alphabet in edge label \"{}\"", label),
+                })
+            }
+            _ if part.iter().all(|(_, escaped)| !escaped)
+                && (text == EPSILON_LABEL || text == EPSILON_CHAR) =>
+            {
+                res.push(None)
+            }
+            [(start, _), ('-', false), (end, _)] if start < end => {
+                res.extend((*start..=*end).map(|c| Some(c.to_string())));
+            }
+            _ => res.push(Some(text)),
+        }
+    }
+    Ok(res)
+}
+
+/// Reads the characters of an edge label, each with whether it was escaped.
+fn unescape_label(label: &str, line: usize) -> Result<Vec<(char, bool)>> {
+    let invalid_escape = || GraphvizParseError::Syntax {
+        line,
+        message: format!("invalid escape in edge label \"{}\"", label),
+    };
+    let mut res = vec![];
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push((c, false));
+            continue;
+        }
+        match chars.next() {
+            Some(c) if c == '\\' || c == ',' || c == '-' => res.push((c, true)),
+            Some('u') => {
+                if chars.next() != Some('{') {
+                    return Err(invalid_escape());
+                }
+                let mut hex = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => hex.push(c),
+                        None => return Err(invalid_escape()),
+                    }
+                }
+                let c = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or_else(invalid_escape)?;
+                res.push((c, true));
+            }
+            _ => return Err(invalid_escape()),
+        }
+    }
+    Ok(res)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Id(String),
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equal,
+    Semicolon,
+    Comma,
+    Arrow,
+}
+
+fn tokenize(dot: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = vec![];
+    let mut chars = dot.chars().peekable();
+    let mut line = 1;
+    let mut at_line_start = true;
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+            at_line_start = true;
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        // Lines starting with `#` are C preprocessor output and ignored by GraphViz.
+        if c == '#' && at_line_start {
+            while chars.peek().is_some_and(|c| *c != '\n') {
+                chars.next();
+            }
+            continue;
+        }
+        at_line_start = false;
+        let token = match c {
+            '{' => Token::LBrace,
+            '}' => Token::RBrace,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '=' => Token::Equal,
+            ';' => Token::Semicolon,
+            ',' => Token::Comma,
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                Token::Arrow
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        None => {
+                            return Err(GraphvizParseError::Syntax {
+                                line,
+                                message: "unterminated comment".to_string(),
+                            })
+                        }
+                        Some('/') if last == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            last = c;
+                        }
+                    }
+                }
+                continue;
+            }
+            '"' => {
+                let start_line = line;
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        None => {
+                            return Err(GraphvizParseError::Syntax {
+                                line: start_line,
+                                message: "unterminated string".to_string(),
+                            })
+                        }
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('"') => id.push('"'),
+                            Some('\\') => id.push('\\'),
+                            Some('\n') => line += 1,
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => continue,
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            id.push(c);
+                        }
+                    }
+                }
+                tokens.push((Token::Id(id), start_line));
+                continue;
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if next.is_alphanumeric() || *next == '_' || *next == '.' {
+                        id.push(*next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                Token::Id(id)
+            }
+            c => {
+                return Err(GraphvizParseError::Syntax {
+                    line,
+                    message: format!("unsupported character '{}'", c),
+                })
+            }
+        };
+        tokens.push((token, line));
+    }
+    Ok(tokens)
+}
+
+struct NodeStmt {
+    id: String,
+    attributes: HashMap<String, String>,
+}
+
+struct EdgeStmt {
+    from: String,
+    to: String,
+    attributes: HashMap<String, String>,
+    line: usize,
+}
+
+struct Graph {
+    nodes: Vec<NodeStmt>,
+    edges: Vec<EdgeStmt>,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    nodes: Vec<NodeStmt>,
+    node_indices: HashMap<String, usize>,
+    node_defaults: HashMap<String, String>,
+    edge_defaults: HashMap<String, String>,
+    edges: Vec<EdgeStmt>,
+}
+
+impl Parser {
+    fn new(tokens: Vec<(Token, usize)>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            nodes: vec![],
+            node_indices: HashMap::new(),
+            node_defaults: HashMap::new(),
+            edge_defaults: HashMap::new(),
+            edges: vec![],
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn error<T>(&self, message: String) -> Result<T> {
+        Err(GraphvizParseError::Syntax {
+            line: self.line(),
+            message,
+        })
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.peek() {
+            Some(token) if *token == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            token => self.error(format!("expected {:?}, found {:?}", expected, token)),
+        }
+    }
+
+    fn expect_id(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.pos += 1;
+                Ok(id)
+            }
+            token => self.error(format!("expected ID, found {:?}", token)),
+        }
+    }
+
+    fn parse_graph(mut self) -> Result<Graph> {
+        let mut keyword = self.expect_id()?;
+        if keyword.eq_ignore_ascii_case("strict") {
+            keyword = self.expect_id()?;
+        }
+        if !keyword.eq_ignore_ascii_case("digraph") {
+            return self.error(format!("expected digraph, found {}", keyword));
+        }
+        if let Some(Token::Id(_)) = self.peek() {
+            self.pos += 1;
+        }
+        self.expect(Token::LBrace)?;
+        loop {
+            match self.peek() {
+                None => return self.error("missing closing brace".to_string()),
+                Some(Token::RBrace) => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(Token::Semicolon) | Some(Token::Comma) => self.pos += 1,
+                Some(_) => self.parse_statement()?,
+            }
+        }
+        if self.peek().is_some() {
+            return self.error("unexpected content after graph".to_string());
+        }
+        Ok(Graph {
+            nodes: self.nodes,
+            edges: self.edges,
+        })
+    }
+
+    fn parse_statement(&mut self) -> Result<()> {
+        let line = self.line();
+        let id = self.expect_id()?;
+        if id.eq_ignore_ascii_case("subgraph") {
+            return self.error("subgraphs are not supported".to_string());
+        }
+        match self.peek() {
+            Some(Token::Equal) => {
+                self.pos += 1;
+                self.expect_id()?;
+            }
+            Some(Token::LBracket) if id.eq_ignore_ascii_case("graph") => {
+                self.parse_attributes()?;
+            }
+            Some(Token::LBracket) if id.eq_ignore_ascii_case("node") => {
+                let attributes = self.parse_attributes()?;
+                self.node_defaults.extend(attributes);
+            }
+            Some(Token::LBracket) if id.eq_ignore_ascii_case("edge") => {
+                let attributes = self.parse_attributes()?;
+                self.edge_defaults.extend(attributes);
+            }
+            Some(Token::Arrow) => {
+                let mut node_ids = vec![id];
+                while self.peek() == Some(&Token::Arrow) {
+                    self.pos += 1;
+                    node_ids.push(self.expect_id()?);
+                }
+                let mut attributes = self.edge_defaults.clone();
+                attributes.extend(self.parse_attributes()?);
+                for node_id in node_ids.iter() {
+                    self.declare_node(node_id, HashMap::new());
+                }
+                for pair in node_ids.windows(2) {
+                    self.edges.push(EdgeStmt {
+                        from: pair[0].clone(),
+                        to: pair[1].clone(),
+                        attributes: attributes.clone(),
+                        line,
+                    });
+                }
+            }
+            _ => {
+                let attributes = self.parse_attributes()?;
+                self.declare_node(&id, attributes);
+            }
+        }
+        Ok(())
+    }
+
+    fn declare_node(&mut self, id: &str, attributes: HashMap<String, String>) {
+        match self.node_indices.get(id) {
+            Some(idx) => self.nodes[*idx].attributes.extend(attributes),
+            None => {
+                let mut node_attributes = self.node_defaults.clone();
+                node_attributes.extend(attributes);
+                self.node_indices.insert(id.to_string(), self.nodes.len());
+                self.nodes.push(NodeStmt {
+                    id: id.to_string(),
+                    attributes: node_attributes,
+                });
+            }
+        }
+    }
+
+    /// Parses zero or more attribute lists like `[a=b, c=d][e=f]`.
+    fn parse_attributes(&mut self) -> Result<HashMap<String, String>> {
+        let mut attributes = HashMap::new();
+        while self.peek() == Some(&Token::LBracket) {
+            self.pos += 1;
+            loop {
+                match self.next() {
+                    Some(Token::RBracket) => break,
+                    Some(Token::Comma) | Some(Token::Semicolon) => continue,
+                    Some(Token::Id(key)) => {
+                        self.expect(Token::Equal)?;
+                        let value = self.expect_id()?;
+                        attributes.insert(key, value);
+                    }
+                    token => {
+                        self.pos -= 1;
+                        return self.error(format!("expected attribute, found {:?}", token));
+                    }
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
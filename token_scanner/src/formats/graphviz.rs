@@ -0,0 +1,308 @@
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{Alphabet, FiniteAutomaton, StateIdentifier};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{Display, Formatter, Result};
+use std::io;
+use std::io::Write;
+
+pub(crate) const EPSILON_LABEL: &str = "&#949;";
+pub(crate) const START_NODE_ID: &str = "__start";
+
+/// Direction in which GraphViz lays out the ranks of the graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RankDir {
+    TopToBottom,
+    LeftToRight,
+    BottomToTop,
+    RightToLeft,
+}
+
+impl Display for RankDir {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            RankDir::TopToBottom => write!(f, "TB"),
+            RankDir::LeftToRight => write!(f, "LR"),
+            RankDir::BottomToTop => write!(f, "BT"),
+            RankDir::RightToLeft => write!(f, "RL"),
+        }
+    }
+}
+
+/// Options controlling how a finite automaton is rendered as a GraphViz dot file.
+///
+/// ```
+/// use token_scanner::formats::graphviz::{GraphvizOptions, RankDir};
+///
+/// let options = GraphvizOptions {
+///     graph_name: "div_by_3".to_string(),
+///     rankdir: RankDir::TopToBottom,
+///     ..GraphvizOptions::default()
+/// };
+/// ```
+#[derive(Clone, Debug)]
+pub struct GraphvizOptions {
+    pub graph_name: String,
+    pub rankdir: RankDir,
+    pub node_shape: String,
+    pub accepted_node_shape: String,
+    pub font_name: Option<String>,
+    /// Fill color of active states in run frames.
+    pub highlight_node_color: String,
+    /// Color of transitions just taken in run frames.
+    pub highlight_edge_color: String,
+}
+
+impl Default for GraphvizOptions {
+    fn default() -> Self {
+        Self {
+            graph_name: "nfa".to_string(),
+            rankdir: RankDir::LeftToRight,
+            node_shape: "circle".to_string(),
+            accepted_node_shape: "doublecircle".to_string(),
+            font_name: None,
+            highlight_node_color: "lightblue".to_string(),
+            highlight_edge_color: "red".to_string(),
+        }
+    }
+}
+
+struct Node {
+    label: String,
+    accepted: bool,
+    highlighted: bool,
+}
+
+/// All transitions between a pair of states, merged into a single edge.
+struct Edge {
+    from: usize,
+    to: usize,
+    labels: BTreeSet<String>,
+    highlighted: bool,
+}
+
+/// Intermediate graph representation of a finite automaton, with states ordered by their labels.
+pub(crate) struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    start: usize,
+}
+
+impl Graph {
+    pub(crate) fn from_automaton<T, U, F>(fa: &F) -> (Self, HashMap<U, usize>)
+    where
+        T: NoneEmptyAlphabet,
+        U: StateIdentifier,
+        F: FiniteAutomaton<T, U> + ?Sized,
+    {
+        let mut states: Vec<U> = fa.states().iter().cloned().collect();
+        states.sort_by_cached_key(|state| (state.to_string(), format!("{:?}", state)));
+        let mut nodes_to_idx = HashMap::new();
+        for (idx, state) in states.iter().enumerate() {
+            nodes_to_idx.insert(state.clone(), idx);
+        }
+
+        let mut edge_labels: BTreeMap<(usize, usize), BTreeSet<String>> = BTreeMap::new();
+        for (i, state) in states.iter().enumerate() {
+            for next_state in fa.epsilon_closure_states(state.clone()) {
+                if *state != next_state {
+                    edge_labels
+                        .entry((i, *nodes_to_idx.get(&next_state).unwrap()))
+                        .or_default()
+                        .insert(EPSILON_LABEL.to_string());
+                }
+            }
+            for alphabet in fa.alphabets() {
+                for next_state in fa.transition(state.clone(), Some(alphabet.clone())) {
+                    edge_labels
+                        .entry((i, *nodes_to_idx.get(&next_state).unwrap()))
+                        .or_default()
+                        .insert(alphabet.to_string());
+                }
+            }
+        }
+        let nodes = states
+            .iter()
+            .map(|state| Node {
+                label: state.to_string(),
+                accepted: fa.accepted_states().contains(state),
+                highlighted: false,
+            })
+            .collect();
+        let edges = edge_labels
+            .into_iter()
+            .map(|((from, to), labels)| Edge {
+                from,
+                to,
+                labels,
+                highlighted: false,
+            })
+            .collect();
+        let graph = Graph {
+            nodes,
+            edges,
+            start: *nodes_to_idx.get(&fa.start_state()).unwrap(),
+        };
+        (graph, nodes_to_idx)
+    }
+
+    /// Highlights the given nodes and every edge containing one of the given
+    /// `(from, to, alphabet)` transitions, where `None` stands for an epsilon transition.
+    pub(crate) fn highlight<T, U>(
+        &mut self,
+        nodes_to_idx: &HashMap<U, usize>,
+        active_states: &HashSet<U>,
+        taken_transitions: &HashSet<(U, U, Alphabet<T>)>,
+    ) where
+        T: NoneEmptyAlphabet,
+        U: StateIdentifier,
+    {
+        let active: HashSet<usize> = active_states
+            .iter()
+            .map(|state| *nodes_to_idx.get(state).unwrap())
+            .collect();
+        for (idx, node) in self.nodes.iter_mut().enumerate() {
+            node.highlighted = active.contains(&idx);
+        }
+        let taken: HashSet<(usize, usize, String)> = taken_transitions
+            .iter()
+            .map(|(from, to, alphabet)| {
+                (
+                    *nodes_to_idx.get(from).unwrap(),
+                    *nodes_to_idx.get(to).unwrap(),
+                    match alphabet {
+                        None => EPSILON_LABEL.to_string(),
+                        Some(val) => val.to_string(),
+                    },
+                )
+            })
+            .collect();
+        for edge in self.edges.iter_mut() {
+            edge.highlighted = edge
+                .labels
+                .iter()
+                .any(|label| taken.contains(&(edge.from, edge.to, label.clone())));
+        }
+    }
+
+    pub(crate) fn render<W: Write>(
+        &self,
+        options: &GraphvizOptions,
+        output: &mut W,
+    ) -> io::Result<()> {
+        writeln!(output, "digraph \"{}\" {{", escape(&options.graph_name))?;
+        writeln!(output, "    rankdir={};", options.rankdir)?;
+        match &options.font_name {
+            None => writeln!(
+                output,
+                "    node [shape=\"{}\"];",
+                escape(&options.node_shape)
+            )?,
+            Some(font_name) => {
+                writeln!(
+                    output,
+                    "    node [shape=\"{}\", fontname=\"{}\"];",
+                    escape(&options.node_shape),
+                    escape(font_name)
+                )?;
+                writeln!(output, "    edge [fontname=\"{}\"];", escape(font_name))?;
+            }
+        }
+        writeln!(output, "    {} [shape=point, style=invis];", START_NODE_ID)?;
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let mut attributes = vec![format!("label=\"{}\"", escape(&node.label))];
+            if node.accepted {
+                attributes.push(format!(
+                    "shape=\"{}\"",
+                    escape(&options.accepted_node_shape)
+                ));
+            }
+            if node.highlighted {
+                attributes.push("style=filled".to_string());
+                attributes.push(format!(
+                    "fillcolor=\"{}\"",
+                    escape(&options.highlight_node_color)
+                ));
+            }
+            writeln!(output, "    STATE{} [{}];", idx, attributes.join(", "))?;
+        }
+        writeln!(output, "    {} -> STATE{};", START_NODE_ID, self.start)?;
+        for edge in self.edges.iter() {
+            let mut attributes = vec![format!("label=\"{}\"", escape(&merge_labels(&edge.labels)))];
+            if edge.highlighted {
+                attributes.push("style=bold".to_string());
+                attributes.push(format!(
+                    "color=\"{}\"",
+                    escape(&options.highlight_edge_color)
+                ));
+            }
+            writeln!(
+                output,
+                "    STATE{} -> STATE{} [{}];",
+                edge.from,
+                edge.to,
+                attributes.join(", ")
+            )?;
+        }
+        writeln!(output, "}}")
+    }
+}
+
+/// Joins the labels of parallel edges with `,`, epsilon first. Runs of three or more consecutive
+/// single-character labels are collapsed into a range such as `a-z`. Backslashes, `,` and `-` in
+/// labels are escaped with a backslash, and whitespace and control characters as `\u{hex}`, so
+/// that `NFA::from_dot_str` reads every label back.
+fn merge_labels(labels: &BTreeSet<String>) -> String {
+    let mut parts = vec![];
+    if labels.contains(EPSILON_LABEL) {
+        parts.push(EPSILON_LABEL.to_string());
+    }
+    let mut ranges: Vec<(char, char)> = vec![];
+    let mut other_labels = vec![];
+    for label in labels.iter().filter(|label| *label != EPSILON_LABEL) {
+        let mut label_chars = label.chars();
+        match (label_chars.next(), label_chars.next()) {
+            (Some(c), None) => match ranges.last_mut() {
+                Some((_, end)) if *end as u32 + 1 == c as u32 => *end = c,
+                _ => ranges.push((c, c)),
+            },
+            _ => other_labels.push(label.clone()),
+        }
+    }
+    for (start, end) in ranges {
+        match end as u32 - start as u32 {
+            0 => parts.push(escape_label(&start.to_string())),
+            1 => parts.extend(vec![
+                escape_label(&start.to_string()),
+                escape_label(&end.to_string()),
+            ]),
+            _ => parts.push(format!(
+                "{}-{}",
+                escape_label(&start.to_string()),
+                escape_label(&end.to_string())
+            )),
+        }
+    }
+    parts.extend(other_labels.iter().map(|label| escape_label(label)));
+    parts.join(",")
+}
+
+fn escape_label(label: &str) -> String {
+    let mut escaped = String::new();
+    for c in label.chars() {
+        match c {
+            '\\' | ',' | '-' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_whitespace() || c.is_control() => {
+                escaped.push_str(&format!("\\u{{{:x}}}", c as u32))
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,412 @@
+//! Reading and writing finite automata in the XML format of [JFLAP](http://www.jflap.org) (`.jff`
+//! files).
+//!
+//! JFLAP identifies states by integer IDs and labels transitions with strings, where an empty
+//! `<read/>` is an epsilon transition. Only transitions reading a single character can be mapped
+//! onto `NFA<char, usize>` and `DFA<char, usize>`.
+//!
+//! ```
+//! use token_scanner::formats::jflap::to_jflap_string;
+//! use token_scanner::{pre_defined_fa, FiniteAutomaton, DFA};
+//!
+//! let jff = to_jflap_string(&pre_defined_fa::bin_str_div_by_3());
+//! let dfa = DFA::from_jflap_str(&jff).unwrap();
+//! assert!(dfa.accept("1001".chars()));
+//! assert_eq!(dfa.minimized().states().len(), 3);
+//! ```
+use crate::{FiniteAutomaton, InvalidAutomatonError, StateIdentifier, DFA, NFA};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Write;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JflapError {
+    /// The input is not well-formed XML.
+    Xml(String),
+    /// The XML document is not a JFLAP finite automaton supported by this crate.
+    InvalidStructure(String),
+    InvalidAutomaton(InvalidAutomatonError),
+}
+
+impl Display for JflapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JflapError::Xml(message) => write!(f, "invalid XML: {}", message),
+            JflapError::InvalidStructure(message) => write!(f, "invalid JFLAP file: {}", message),
+            JflapError::InvalidAutomaton(err) => write!(f, "invalid automaton: {}", err),
+        }
+    }
+}
+
+impl Error for JflapError {}
+
+impl From<InvalidAutomatonError> for JflapError {
+    fn from(err: InvalidAutomatonError) -> Self {
+        JflapError::InvalidAutomaton(err)
+    }
+}
+
+impl From<quick_xml::Error> for JflapError {
+    fn from(err: quick_xml::Error) -> Self {
+        JflapError::Xml(err.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, JflapError>;
+
+impl NFA<char, usize> {
+    /// Parses a JFLAP finite automaton. States keep their JFLAP IDs, names and coordinates are
+    /// ignored.
+    pub fn from_jflap_str(xml: &str) -> Result<Self> {
+        let parsed = ParsedAutomaton::parse(xml)?;
+        let mut transition_map: HashMap<usize, HashMap<Option<char>, HashSet<usize>>> =
+            HashMap::new();
+        let mut alphabets = HashSet::new();
+        for (from_state, alphabet, to_state) in parsed.transitions {
+            if let Some(val) = alphabet {
+                alphabets.insert(val);
+            }
+            transition_map
+                .entry(from_state)
+                .or_default()
+                .entry(alphabet)
+                .or_default()
+                .insert(to_state);
+        }
+        Ok(NFA::try_from_formal_strict(
+            parsed.states,
+            alphabets,
+            parsed.start_state,
+            parsed.accepted_states,
+            transition_map,
+        )?)
+    }
+}
+
+impl DFA<char, usize> {
+    /// Parses a JFLAP finite automaton without epsilon transitions and with at most one transition
+    /// per state and alphabet. JFLAP does not require DFAs to be complete, so missing transitions
+    /// go to an added trap state, whose ID is one larger than the largest ID in the file.
+    pub fn from_jflap_str(xml: &str) -> Result<Self> {
+        let parsed = ParsedAutomaton::parse(xml)?;
+        let mut states = parsed.states;
+        let mut transition_map: HashMap<usize, HashMap<char, usize>> = HashMap::new();
+        let mut alphabets = HashSet::new();
+        for (from_state, alphabet, to_state) in parsed.transitions {
+            let alphabet = match alphabet {
+                None => {
+                    return Err(JflapError::InvalidStructure(format!(
+                        "epsilon transition from state {} in a DFA",
+                        from_state
+                    )))
+                }
+                Some(val) => val,
+            };
+            alphabets.insert(alphabet);
+            let map = transition_map.entry(from_state).or_default();
+            match map.get(&alphabet) {
+                Some(existing) if *existing != to_state => {
+                    return Err(JflapError::InvalidStructure(format!(
+                        "state {} has more than one transition for alphabet {}",
+                        from_state, alphabet
+                    )))
+                }
+                _ => map.insert(alphabet, to_state),
+            };
+        }
+        let is_complete = states.iter().all(|state| {
+            transition_map
+                .get(state)
+                .map_or(alphabets.is_empty(), |map| map.len() == alphabets.len())
+        });
+        if !is_complete {
+            let trap_state = states.iter().max().map_or(0, |max| max + 1);
+            states.insert(trap_state);
+            for state in states.iter() {
+                let map = transition_map.entry(*state).or_default();
+                for alphabet in alphabets.iter() {
+                    map.entry(*alphabet).or_insert(trap_state);
+                }
+            }
+        }
+        Ok(DFA::try_from_formal_strict(
+            states,
+            alphabets,
+            parsed.start_state,
+            parsed.accepted_states,
+            transition_map,
+        )?)
+    }
+}
+
+/// Renders any finite automaton over `char` as a JFLAP file. States are numbered in the order of
+/// their labels, named by their labels, and placed on a circle.
+pub fn to_jflap_string<U, F>(fa: &F) -> String
+where
+    U: StateIdentifier,
+    F: FiniteAutomaton<char, U> + ?Sized,
+{
+    let mut output = Vec::new();
+    write_jflap(fa, &mut output).expect("Writing to a Vec<u8> cannot fail.");
+    String::from_utf8(output).expect("JFLAP output is always valid UTF-8.")
+}
+
+/// Writes any finite automaton over `char` as a JFLAP file to `output`, see `to_jflap_string`.
+pub fn write_jflap<U, F, W>(fa: &F, output: &mut W) -> io::Result<()>
+where
+    U: StateIdentifier,
+    F: FiniteAutomaton<char, U> + ?Sized,
+    W: Write,
+{
+    let mut states: Vec<U> = fa.states().iter().cloned().collect();
+    states.sort_by_cached_key(|state| (state.to_string(), format!("{:?}", state)));
+    let state_to_id: HashMap<U, usize> = states
+        .iter()
+        .enumerate()
+        .map(|(id, state)| (state.clone(), id))
+        .collect();
+    let mut alphabets: Vec<char> = fa.alphabets().iter().cloned().collect();
+    alphabets.sort_unstable();
+
+    writeln!(
+        output,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>"
+    )?;
+    writeln!(output, "<structure>")?;
+    writeln!(output, "\t<type>fa</type>")?;
+    writeln!(output, "\t<automaton>")?;
+    let radius = 50.0 * states.len() as f64 / PI;
+    for (id, state) in states.iter().enumerate() {
+        let angle = 2.0 * PI * id as f64 / states.len() as f64;
+        writeln!(
+            output,
+            "\t\t<state id=\"{}\" name=\"{}\">",
+            id,
+            escape(&state.to_string())
+        )?;
+        writeln!(
+            output,
+            "\t\t\t<x>{:.1}</x>",
+            100.0 + radius * (1.0 + angle.cos())
+        )?;
+        writeln!(
+            output,
+            "\t\t\t<y>{:.1}</y>",
+            100.0 + radius * (1.0 + angle.sin())
+        )?;
+        if *state == fa.start_state() {
+            writeln!(output, "\t\t\t<initial/>")?;
+        }
+        if fa.accepted_states().contains(state) {
+            writeln!(output, "\t\t\t<final/>")?;
+        }
+        writeln!(output, "\t\t</state>")?;
+    }
+    for (from_id, state) in states.iter().enumerate() {
+        let mut transitions = vec![];
+        for to_state in fa.transition(state.clone(), None) {
+            transitions.push((None, *state_to_id.get(&to_state).unwrap()));
+        }
+        for alphabet in alphabets.iter() {
+            for to_state in fa.transition(state.clone(), Some(*alphabet)) {
+                transitions.push((Some(*alphabet), *state_to_id.get(&to_state).unwrap()));
+            }
+        }
+        transitions.sort_unstable();
+        for (alphabet, to_id) in transitions {
+            writeln!(output, "\t\t<transition>")?;
+            writeln!(output, "\t\t\t<from>{}</from>", from_id)?;
+            writeln!(output, "\t\t\t<to>{}</to>", to_id)?;
+            match alphabet {
+                None => writeln!(output, "\t\t\t<read/>")?,
+                Some(val) => writeln!(output, "\t\t\t<read>{}</read>", escape(&val.to_string()))?,
+            }
+            writeln!(output, "\t\t</transition>")?;
+        }
+    }
+    writeln!(output, "\t</automaton>")?;
+    writeln!(output, "</structure>")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+struct ParsedAutomaton {
+    states: HashSet<usize>,
+    start_state: usize,
+    accepted_states: HashSet<usize>,
+    transitions: Vec<(usize, Option<char>, usize)>,
+}
+
+#[derive(Default)]
+struct PartialTransition {
+    from: Option<usize>,
+    to: Option<usize>,
+    read: Option<String>,
+}
+
+impl ParsedAutomaton {
+    fn parse(xml: &str) -> Result<Self> {
+        // Text is not trimmed, as `<read> </read>` reads a space. Whitespace between elements is
+        // cleared at the start of every element, and ignored around state IDs and the type.
+        let mut reader = Reader::from_str(xml);
+
+        let mut states = HashSet::new();
+        let mut start_states = vec![];
+        let mut accepted_states = HashSet::new();
+        let mut transitions = vec![];
+        let mut cur_state = None;
+        let mut cur_transition: Option<PartialTransition> = None;
+        let mut text = String::new();
+        loop {
+            match reader.read_event()? {
+                Event::Eof => break,
+                Event::Start(element) => {
+                    text.clear();
+                    match element.local_name().as_ref() {
+                        b"state" => cur_state = Some(parse_state_id(&element)?),
+                        b"transition" => cur_transition = Some(PartialTransition::default()),
+                        _ => {}
+                    }
+                }
+                Event::Empty(element) => match element.local_name().as_ref() {
+                    b"state" => insert_state(&mut states, parse_state_id(&element)?)?,
+                    b"initial" => match cur_state {
+                        None => return Err(misplaced("initial")),
+                        Some(state) => start_states.push(state),
+                    },
+                    b"final" => match cur_state {
+                        None => return Err(misplaced("final")),
+                        Some(state) => {
+                            accepted_states.insert(state);
+                        }
+                    },
+                    b"read" => match cur_transition.as_mut() {
+                        None => return Err(misplaced("read")),
+                        Some(transition) => transition.read = Some(String::new()),
+                    },
+                    _ => {}
+                },
+                Event::Text(content) => {
+                    text.push_str(&content.unescape()?);
+                }
+                Event::CData(content) => {
+                    text.push_str(&reader.decoder().decode(&content)?);
+                }
+                Event::End(element) => {
+                    match element.local_name().as_ref() {
+                        b"type" if text.trim() != "fa" => {
+                            return Err(JflapError::InvalidStructure(format!(
+                                "expected a finite automaton, found type {}",
+                                text.trim()
+                            )))
+                        }
+                        b"state" => match cur_state.take() {
+                            None => return Err(misplaced("state")),
+                            Some(state) => insert_state(&mut states, state)?,
+                        },
+                        b"from" | b"to" | b"read" => {
+                            let transition = match cur_transition.as_mut() {
+                                None => {
+                                    return Err(misplaced(&String::from_utf8_lossy(
+                                        element.local_name().as_ref(),
+                                    )))
+                                }
+                                Some(val) => val,
+                            };
+                            match element.local_name().as_ref() {
+                                b"from" => transition.from = Some(parse_id(&text)?),
+                                b"to" => transition.to = Some(parse_id(&text)?),
+                                _ => transition.read = Some(text.clone()),
+                            }
+                        }
+                        b"transition" => match cur_transition.take() {
+                            Some(PartialTransition {
+                                from: Some(from),
+                                to: Some(to),
+                                read: Some(read),
+                            }) => {
+                                let mut chars = read.chars();
+                                let alphabet = match (chars.next(), chars.next()) {
+                                    (None, _) => None,
+                                    (Some(c), None) => Some(c),
+                                    _ => {
+                                        return Err(JflapError::InvalidStructure(format!(
+                                            "transition from state {} reads \"{}\", only single \
+                                             characters are supported",
+                                            from, read
+                                        )))
+                                    }
+                                };
+                                transitions.push((from, alphabet, to));
+                            }
+                            _ => {
+                                return Err(JflapError::InvalidStructure(
+                                    "transition without from, to or read".to_string(),
+                                ))
+                            }
+                        },
+                        _ => {}
+                    }
+                    text.clear();
+                }
+                _ => {}
+            }
+        }
+        if start_states.len() != 1 {
+            return Err(JflapError::InvalidStructure(format!(
+                "expected exactly one initial state, found {}",
+                start_states.len()
+            )));
+        }
+        Ok(Self {
+            states,
+            start_state: start_states.pop().unwrap(),
+            accepted_states,
+            transitions,
+        })
+    }
+}
+
+fn insert_state(states: &mut HashSet<usize>, state: usize) -> Result<()> {
+    if states.insert(state) {
+        Ok(())
+    } else {
+        Err(JflapError::InvalidStructure(format!(
+            "duplicate state ID {}",
+            state
+        )))
+    }
+}
+
+fn misplaced(element: &str) -> JflapError {
+    JflapError::InvalidStructure(format!("misplaced <{}> element", element))
+}
+
+fn parse_id(text: &str) -> Result<usize> {
+    text.trim()
+        .parse()
+        .map_err(|_| JflapError::InvalidStructure(format!("invalid state ID \"{}\"", text)))
+}
+
+fn parse_state_id(element: &BytesStart) -> Result<usize> {
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|err| JflapError::Xml(err.to_string()))?;
+        if attribute.key.local_name().as_ref() == b"id" {
+            return parse_id(&attribute.unescape_value()?);
+        }
+    }
+    Err(JflapError::InvalidStructure(
+        "state without id attribute".to_string(),
+    ))
+}
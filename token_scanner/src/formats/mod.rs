@@ -0,0 +1,8 @@
+pub mod binary;
+pub mod dot_import;
+pub mod graphviz;
+pub mod jflap;
+pub mod rust_codegen;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod table;
@@ -0,0 +1,334 @@
+//! Generates the Rust source of a standalone scanner from a `DFA<char, U>`. The generated items
+//! only use `core` and `std` types, so the scanner runs without `token_scanner` or any
+//! `HashMap`. The DFA is minimized first, and states from which no accepted state can be reached
+//! are left out, so that scanning stops as soon as no longer token can be found.
+//!
+//! The generated source defines:
+//!
+//! - `START_STATE: u32`, always `0`;
+//! - `fn next_state(state: u32, c: char) -> Option<u32>`;
+//! - `fn is_accepting(state: u32) -> bool`;
+//! - `fn accept(input: &str) -> bool`, like `FiniteAutomaton::accept`;
+//! - `fn next_token(input: &str) -> Option<usize>`, the length in bytes of the longest non-empty
+//!   prefix of `input` that is accepted.
+//!
+//! It contains no inner attributes, so it can be included into a module with `include!`, for
+//! example from the output directory of a build script.
+use crate::custom_traits::finite_automaton::co_reachable_states;
+use crate::{FiniteAutomaton, StateIdentifier, DFA};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Marks transitions to states from which no accepted state can be reached.
+const DEAD: u32 = u32::MAX;
+
+/// How the generated scanner encodes its transitions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CodegenStyle {
+    /// Static tables of character ranges, transitions by class of characters, and accepting
+    /// states.
+    #[default]
+    Tables,
+    /// A `match` over the characters read in every state.
+    Match,
+}
+
+/// The minimized DFA with states `0..n`, the start state being `0`, and its classes of characters
+/// with the same transitions from every state, see `AlphabetClasses`.
+struct Scanner {
+    /// The characters with a transition to a live state, sorted.
+    chars: Vec<char>,
+    /// `classes[idx]` is the class of `chars[idx]`.
+    classes: Vec<u32>,
+    /// `transitions[state][class]` is the state after reading a character of `class`, or `DEAD`.
+    transitions: Vec<Vec<u32>>,
+    accepting: Vec<bool>,
+}
+
+impl Scanner {
+    fn new<U>(dfa: &DFA<char, U>) -> Self
+    where
+        U: StateIdentifier,
+    {
+        let dfa = dfa.minimized().relabeled();
+        let live = co_reachable_states(&dfa);
+        let mut new_states = vec![DEAD; dfa.states().len()];
+        let mut state_count = 0;
+        for (state, new_state) in new_states.iter_mut().enumerate() {
+            if state == dfa.start_state() || live.contains(&state) {
+                *new_state = state_count;
+                state_count += 1;
+            }
+        }
+        let states: Vec<usize> = (0..dfa.states().len())
+            .filter(|state| new_states[*state] != DEAD)
+            .collect();
+        let alphabet_classes = dfa.alphabet_classes();
+        let mut chars: Vec<(char, u32)> = vec![];
+        let mut transitions = vec![vec![]; state_count as usize];
+        for (class, alphabets) in alphabet_classes.classes().iter().enumerate() {
+            let representative = alphabet_classes.representative(class);
+            let to_states: Vec<u32> = states
+                .iter()
+                .map(|state| {
+                    let map = dfa.transition_map().get(state).unwrap();
+                    new_states[*map.get(representative).unwrap()]
+                })
+                .collect();
+            // Characters leading to dead states only are the same as characters without
+            // transitions.
+            if to_states.iter().all(|to_state| *to_state == DEAD) {
+                continue;
+            }
+            let new_class = transitions[0].len() as u32;
+            for (row, to_state) in transitions.iter_mut().zip(to_states) {
+                row.push(to_state);
+            }
+            chars.extend(alphabets.iter().map(|c| (*c, new_class)));
+        }
+        chars.sort_unstable();
+        let accepting = states
+            .iter()
+            .map(|state| dfa.accepted_states().contains(state))
+            .collect();
+        Self {
+            chars: chars.iter().map(|(c, _)| *c).collect(),
+            classes: chars.iter().map(|(_, class)| *class).collect(),
+            transitions,
+            accepting,
+        }
+    }
+
+    fn class_count(&self) -> usize {
+        self.transitions.first().map_or(0, Vec::len)
+    }
+
+    /// The state after reading every character of `chars` in `state`.
+    fn char_transitions(&self, state: usize) -> Vec<u32> {
+        self.classes
+            .iter()
+            .map(|class| self.transitions[state][*class as usize])
+            .collect()
+    }
+
+    /// Merges consecutive characters with the same value into ranges `(start, end, value)`.
+    fn ranges(&self, values: &[u32]) -> Vec<(char, char, u32)> {
+        let mut ranges: Vec<(char, char, u32)> = vec![];
+        for (c, value) in self.chars.iter().zip(values.iter()) {
+            match ranges.last_mut() {
+                Some((_, end, last_value))
+                    if *last_value == *value && *end as u32 + 1 == *c as u32 =>
+                {
+                    *end = *c
+                }
+                _ => ranges.push((*c, *c, *value)),
+            }
+        }
+        ranges
+    }
+}
+
+fn char_pattern(start: char, end: char) -> String {
+    if start == end {
+        format!("{:?}", start)
+    } else {
+        format!("{:?}..={:?}", start, end)
+    }
+}
+
+fn state_name(state: u32) -> String {
+    if state == DEAD {
+        "DEAD".to_string()
+    } else {
+        state.to_string()
+    }
+}
+
+impl<U> DFA<char, U>
+where
+    U: StateIdentifier,
+{
+    /// Generates the source of a standalone scanner for the DFA, see the `formats::rust_codegen`
+    /// module.
+    /// ```
+    /// use token_scanner::formats::rust_codegen::CodegenStyle;
+    /// use token_scanner::pre_defined_fa;
+    ///
+    /// let source = pre_defined_fa::bin_str_div_by_3().to_rust_source(CodegenStyle::Match);
+    /// assert!(source.contains("pub fn next_token(input: &str) -> Option<usize>"));
+    /// assert!(source.contains("'0' => Some(0),"));
+    /// ```
+    pub fn to_rust_source(&self, style: CodegenStyle) -> String {
+        let scanner = Scanner::new(self);
+        let mut source = String::new();
+        writeln!(
+            source,
+            "// Generated by token_scanner from a DFA with {} states. Do not edit.",
+            scanner.accepting.len()
+        )
+        .unwrap();
+        source.push('\n');
+        match style {
+            CodegenStyle::Tables => write_tables(&scanner, &mut source),
+            CodegenStyle::Match => write_match(&scanner, &mut source),
+        }
+        source.push_str(COMMON_ITEMS);
+        source
+    }
+}
+
+fn write_tables(scanner: &Scanner, source: &mut String) {
+    let class_count = scanner.class_count();
+    let ranges = scanner.ranges(&scanner.classes);
+    writeln!(source, "const CLASS_COUNT: usize = {};\n", class_count).unwrap();
+    source.push_str(
+        "// Marks transitions to states from which no accepted state can be reached.\n\
+         const DEAD: u32 = u32::MAX;\n\n",
+    );
+    source.push_str("// Sorted, disjoint character ranges with their class.\n");
+    writeln!(
+        source,
+        "static RANGES: [(char, char, usize); {}] = [",
+        ranges.len()
+    )
+    .unwrap();
+    for (start, end, class) in ranges.iter() {
+        writeln!(source, "    ({:?}, {:?}, {}),", start, end, class).unwrap();
+    }
+    source.push_str("];\n\n");
+    source.push_str("// `TRANSITIONS[state * CLASS_COUNT + class]`\n");
+    writeln!(
+        source,
+        "static TRANSITIONS: [u32; {}] = [",
+        scanner.accepting.len() * class_count
+    )
+    .unwrap();
+    for row in scanner.transitions.iter().filter(|row| !row.is_empty()) {
+        let cells: Vec<String> = row.iter().cloned().map(state_name).collect();
+        writeln!(source, "    {},", cells.join(", ")).unwrap();
+    }
+    source.push_str("];\n\n");
+    writeln!(
+        source,
+        "static ACCEPTING: [bool; {}] = [",
+        scanner.accepting.len()
+    )
+    .unwrap();
+    for accepting in scanner.accepting.iter() {
+        writeln!(source, "    {},", accepting).unwrap();
+    }
+    source.push_str("];\n\n");
+    source.push_str(
+        "fn class(c: char) -> Option<usize> {
+    RANGES
+        .binary_search_by(|&(start, end, _)| {
+            if end < c {
+                std::cmp::Ordering::Less
+            } else if start > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .ok()
+        .map(|idx| RANGES[idx].2)
+}
+
+/// Returns the state after reading `c`, or `None` if no accepted state can be reached anymore.
+pub fn next_state(state: u32, c: char) -> Option<u32> {
+    let next_state = TRANSITIONS[state as usize * CLASS_COUNT + class(c)?];
+    if next_state == DEAD {
+        None
+    } else {
+        Some(next_state)
+    }
+}
+
+pub fn is_accepting(state: u32) -> bool {
+    ACCEPTING[state as usize]
+}
+",
+    );
+}
+
+fn write_match(scanner: &Scanner, source: &mut String) {
+    source.push_str(
+        "/// Returns the state after reading `c`, or `None` if no accepted state can be reached anymore.
+pub fn next_state(state: u32, c: char) -> Option<u32> {
+    match state {
+",
+    );
+    for state in 0..scanner.accepting.len() {
+        let mut to_state_to_patterns: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+        for (start, end, to_state) in scanner.ranges(&scanner.char_transitions(state)) {
+            if to_state != DEAD {
+                to_state_to_patterns
+                    .entry(to_state)
+                    .or_default()
+                    .push(char_pattern(start, end));
+            }
+        }
+        if to_state_to_patterns.is_empty() {
+            writeln!(source, "        {} => None,", state).unwrap();
+            continue;
+        }
+        writeln!(source, "        {} => match c {{", state).unwrap();
+        for (to_state, patterns) in to_state_to_patterns {
+            writeln!(
+                source,
+                "            {} => Some({}),",
+                patterns.join(" | "),
+                to_state
+            )
+            .unwrap();
+        }
+        source.push_str("            _ => None,\n        },\n");
+    }
+    source.push_str("        _ => None,\n    }\n}\n\n");
+    let accepting: Vec<String> = scanner
+        .accepting
+        .iter()
+        .enumerate()
+        .filter(|(_, accepting)| **accepting)
+        .map(|(state, _)| state.to_string())
+        .collect();
+    source.push_str("pub fn is_accepting(state: u32) -> bool {\n");
+    if accepting.is_empty() {
+        source.push_str("    let _ = state;\n    false\n");
+    } else {
+        writeln!(source, "    matches!(state, {})", accepting.join(" | ")).unwrap();
+    }
+    source.push_str("}\n");
+}
+
+const COMMON_ITEMS: &str = "
+pub const START_STATE: u32 = 0;
+
+/// Whether all of `input` is accepted.
+pub fn accept(input: &str) -> bool {
+    let mut state = START_STATE;
+    for c in input.chars() {
+        state = match next_state(state, c) {
+            Some(state) => state,
+            None => return false,
+        };
+    }
+    is_accepting(state)
+}
+
+/// Returns the length in bytes of the longest non-empty prefix of `input` that is accepted.
+pub fn next_token(input: &str) -> Option<usize> {
+    let mut state = START_STATE;
+    let mut len = None;
+    for (offset, c) in input.char_indices() {
+        state = match next_state(state, c) {
+            Some(state) => state,
+            None => break,
+        };
+        if is_accepting(state) {
+            len = Some(offset + c.len_utf8());
+        }
+    }
+    len
+}
+";
@@ -0,0 +1,206 @@
+//! `Serialize` and `Deserialize` implementations for `NFA`, `DFA` and `CommunicativeHashSet`,
+//! available with the `serde` feature.
+//!
+//! Automata are serialized by their formal definition, with transitions as a list of
+//! `{from, alphabet, to}` entries (`alphabet` is `null` for epsilon transitions), so formats
+//! without non-string map keys like JSON are supported. Elements are sorted to keep the output
+//! stable. Deserialization goes through `try_from_formal`, so invalid automata are rejected with
+//! the same checks as `from_formal`, and transitions from or to undeclared states are rejected as
+//! well.
+//!
+//! ```
+//! use token_scanner::{pre_defined_fa, FiniteAutomaton, DFA};
+//!
+//! let dfa = pre_defined_fa::bin_str_div_by_3();
+//! let json = serde_json::to_string(&dfa).unwrap();
+//! let deserialized: DFA<char, u8> = serde_json::from_str(&json).unwrap();
+//! assert!(deserialized.accept("1001".chars()));
+//! ```
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{CommunicativeHashSet, FiniteAutomaton, StateIdentifier, DFA, NFA};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+#[derive(Serialize, Deserialize)]
+struct FormalDefinition<T, U, A, V> {
+    states: Vec<U>,
+    alphabets: Vec<T>,
+    start_state: U,
+    accepted_states: Vec<U>,
+    transitions: Vec<Transition<U, A, V>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Transition<U, A, V> {
+    from: U,
+    alphabet: A,
+    to: V,
+}
+
+fn sorted<'a, V, I>(values: I) -> Vec<&'a V>
+where
+    V: Debug + 'a,
+    I: IntoIterator<Item = &'a V>,
+{
+    let mut res: Vec<&V> = values.into_iter().collect();
+    res.sort_by_cached_key(|val| format!("{:?}", val));
+    res
+}
+
+impl<T, U> Serialize for NFA<T, U>
+where
+    T: NoneEmptyAlphabet + Serialize,
+    U: StateIdentifier + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut transitions = vec![];
+        for from_state in sorted(self.transition_map().keys()) {
+            let map = self.transition_map().get(from_state).unwrap();
+            for alphabet in sorted(map.keys()) {
+                transitions.push(Transition {
+                    from: from_state,
+                    alphabet: alphabet.as_ref(),
+                    to: sorted(map.get(alphabet).unwrap()),
+                });
+            }
+        }
+        let start_state = self.start_state();
+        FormalDefinition {
+            states: sorted(self.states()),
+            alphabets: sorted(self.alphabets()),
+            start_state: &start_state,
+            accepted_states: sorted(self.accepted_states()),
+            transitions,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T, U> Deserialize<'de> for NFA<T, U>
+where
+    T: NoneEmptyAlphabet + Deserialize<'de>,
+    U: StateIdentifier + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let definition: FormalDefinition<T, U, Option<T>, Vec<U>> =
+            FormalDefinition::deserialize(deserializer)?;
+        let mut transition_map: HashMap<U, HashMap<Option<T>, HashSet<U>>> = HashMap::new();
+        for transition in definition.transitions {
+            transition_map
+                .entry(transition.from)
+                .or_default()
+                .entry(transition.alphabet)
+                .or_default()
+                .extend(transition.to);
+        }
+        NFA::try_from_formal_strict(
+            definition.states.into_iter().collect(),
+            definition.alphabets.into_iter().collect(),
+            definition.start_state,
+            definition.accepted_states.into_iter().collect(),
+            transition_map,
+        )
+        .map_err(D::Error::custom)
+    }
+}
+
+impl<T, U> Serialize for DFA<T, U>
+where
+    T: NoneEmptyAlphabet + Serialize,
+    U: StateIdentifier + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut transitions = vec![];
+        for from_state in sorted(self.transition_map().keys()) {
+            let map = self.transition_map().get(from_state).unwrap();
+            for alphabet in sorted(map.keys()) {
+                transitions.push(Transition {
+                    from: from_state,
+                    alphabet,
+                    to: map.get(alphabet).unwrap(),
+                });
+            }
+        }
+        let start_state = self.start_state();
+        FormalDefinition {
+            states: sorted(self.states()),
+            alphabets: sorted(self.alphabets()),
+            start_state: &start_state,
+            accepted_states: sorted(self.accepted_states()),
+            transitions,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T, U> Deserialize<'de> for DFA<T, U>
+where
+    T: NoneEmptyAlphabet + Deserialize<'de>,
+    U: StateIdentifier + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let definition: FormalDefinition<T, U, T, U> = FormalDefinition::deserialize(deserializer)?;
+        let mut transition_map: HashMap<U, HashMap<T, U>> = HashMap::new();
+        for transition in definition.transitions {
+            let map = transition_map.entry(transition.from.clone()).or_default();
+            match map.get(&transition.alphabet) {
+                Some(to_state) if *to_state != transition.to => {
+                    return Err(D::Error::custom(format!(
+                        "more than one transition from state {} for alphabet {}",
+                        transition.from, transition.alphabet
+                    )))
+                }
+                _ => map.insert(transition.alphabet, transition.to),
+            };
+        }
+        DFA::try_from_formal_strict(
+            definition.states.into_iter().collect(),
+            definition.alphabets.into_iter().collect(),
+            definition.start_state,
+            definition.accepted_states.into_iter().collect(),
+            transition_map,
+        )
+        .map_err(D::Error::custom)
+    }
+}
+
+impl<T> Serialize for CommunicativeHashSet<T>
+where
+    T: Eq + Hash + Debug + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(sorted(&self.hashset))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for CommunicativeHashSet<T>
+where
+    T: Eq + Hash + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(CommunicativeHashSet::from(HashSet::deserialize(
+            deserializer,
+        )?))
+    }
+}
@@ -0,0 +1,437 @@
+//! A plain-text transition table format that is easy to read and edit by hand:
+//!
+//! ```text
+//!        0     1     ε
+//! -> q0  {q1}  {}    {}
+//!    q1  {}    {q2}  {q0}
+//! *  q2  {q2}  {q1}  {}
+//! ```
+//!
+//! The header row lists the alphabets, followed by an optional `ε` column for epsilon transitions.
+//! Every other row starts with the optional markers `->` for the start state and `*` for accepted
+//! states, followed by the state and one cell per column. A cell is either a set of states like
+//! `{q1,q2}`, or a single state without braces, which is how deterministic automata are printed.
+//! Lines starting with `#` are comments. States and alphabets cannot contain whitespace, `{`, `}`
+//! or `,`.
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use crate::{FiniteAutomaton, InvalidAutomatonError, StateIdentifier, DFA, NFA};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+
+const EPSILON: &str = "ε";
+const START_MARKER: &str = "->";
+const ACCEPTED_MARKER: &str = "*";
+
+/// Displays a finite automaton as a transition table, created by
+/// `FiniteAutomaton::transition_table`.
+pub struct TransitionTable<'a, T, U, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    fa: &'a F,
+    phantom: PhantomData<(T, U)>,
+}
+
+impl<'a, T, U, F> TransitionTable<'a, T, U, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    pub fn new(fa: &'a F) -> Self {
+        Self {
+            fa,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, U, F> Display for TransitionTable<'a, T, U, F>
+where
+    T: NoneEmptyAlphabet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let start_state = self.fa.start_state();
+        let mut states: Vec<U> = self.fa.states().iter().cloned().collect();
+        states.sort_by_cached_key(|state| {
+            (
+                *state != start_state,
+                state.to_string(),
+                format!("{:?}", state),
+            )
+        });
+        let mut alphabets: Vec<Option<T>> = self.fa.alphabets().iter().cloned().map(Some).collect();
+        alphabets.sort_by_cached_key(|alphabet| alphabet.as_ref().unwrap().to_string());
+        let has_epsilon = states
+            .iter()
+            .any(|state| !self.fa.transition(state.clone(), None).is_empty());
+        if has_epsilon {
+            alphabets.push(None);
+        }
+
+        let transitions: Vec<Vec<Vec<String>>> = states
+            .iter()
+            .map(|state| {
+                alphabets
+                    .iter()
+                    .map(|alphabet| {
+                        let mut to_states: Vec<String> = self
+                            .fa
+                            .transition(state.clone(), alphabet.clone())
+                            .iter()
+                            .map(|to_state| to_state.to_string())
+                            .collect();
+                        to_states.sort();
+                        to_states
+                    })
+                    .collect()
+            })
+            .collect();
+        let deterministic = !has_epsilon
+            && transitions
+                .iter()
+                .all(|row| row.iter().all(|cell| cell.len() == 1));
+
+        let mut rows = vec![];
+        let mut header = vec![String::new(), String::new()];
+        header.extend(alphabets.iter().map(|alphabet| match alphabet {
+            None => EPSILON.to_string(),
+            Some(val) => val.to_string(),
+        }));
+        rows.push(header);
+        for (state, cells) in states.iter().zip(transitions) {
+            let mut markers = String::new();
+            if *state == start_state {
+                markers.push_str(START_MARKER);
+            }
+            if self.fa.accepted_states().contains(state) {
+                markers.push_str(ACCEPTED_MARKER);
+            }
+            let mut row = vec![markers, state.to_string()];
+            row.extend(cells.into_iter().map(|cell| {
+                if deterministic {
+                    cell.join(",")
+                } else {
+                    format!("{{{}}}", cell.join(","))
+                }
+            }));
+            rows.push(row);
+        }
+
+        let column_count = rows[0].len();
+        let widths: Vec<usize> = (0..column_count)
+            .map(|idx| {
+                rows.iter()
+                    .map(|row| row[idx].chars().count())
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+        for row in rows {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+            writeln!(f, "{}", line.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TableParseError {
+    Syntax { line: usize, message: String },
+    InvalidAutomaton(InvalidAutomatonError),
+}
+
+impl Display for TableParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableParseError::Syntax { line, message } => {
+                write!(f, "syntax error on line {}: {}", line, message)
+            }
+            TableParseError::InvalidAutomaton(err) => write!(f, "invalid automaton: {}", err),
+        }
+    }
+}
+
+impl Error for TableParseError {}
+
+impl From<InvalidAutomatonError> for TableParseError {
+    fn from(err: InvalidAutomatonError) -> Self {
+        TableParseError::InvalidAutomaton(err)
+    }
+}
+
+type Result<T> = std::result::Result<T, TableParseError>;
+
+impl NFA<String, String> {
+    /// Parses a transition table, see the `formats::table` module for the format.
+    /// ```
+    /// use token_scanner::{FiniteAutomaton, NFA};
+    ///
+    /// let nfa = NFA::from_transition_table(
+    ///     "
+    ///            a       b
+    ///     -> q0  {q0,q1} {q0}
+    ///        q1  {}      {q2}
+    ///     *  q2  {}      {}
+    ///     ",
+    /// )
+    /// .unwrap();
+    /// assert!(nfa.accept("bab".chars().map(|c| c.to_string())));
+    /// println!("{}", nfa.transition_table());
+    /// ```
+    pub fn from_transition_table(table: &str) -> Result<Self> {
+        let parsed = ParsedTable::parse(table)?;
+        let mut transition_map: HashMap<String, HashMap<Option<String>, HashSet<String>>> =
+            HashMap::new();
+        for (state, alphabet, to_states, _) in parsed.transitions {
+            if !to_states.is_empty() {
+                transition_map
+                    .entry(state)
+                    .or_default()
+                    .entry(alphabet)
+                    .or_default()
+                    .extend(to_states);
+            }
+        }
+        Ok(NFA::try_from_formal_strict(
+            parsed.states,
+            parsed.alphabets,
+            parsed.start_state,
+            parsed.accepted_states,
+            transition_map,
+        )?)
+    }
+}
+
+impl DFA<String, String> {
+    /// Parses a transition table where every cell of every state contains exactly one state, and
+    /// which has no epsilon transitions, see the `formats::table` module for the format.
+    /// ```
+    /// use token_scanner::{FiniteAutomaton, DFA};
+    ///
+    /// let dfa = DFA::from_transition_table(
+    ///     "
+    ///             0   1
+    ///     ->* s0  s0  s1
+    ///         s1  s2  s0
+    ///         s2  s1  s2
+    ///     ",
+    /// )
+    /// .unwrap();
+    /// assert!(dfa.accept("1001".chars().map(|c| c.to_string())));
+    /// ```
+    pub fn from_transition_table(table: &str) -> Result<Self> {
+        let parsed = ParsedTable::parse(table)?;
+        let mut transition_map: HashMap<String, HashMap<String, String>> = parsed
+            .states
+            .iter()
+            .map(|state| (state.clone(), HashMap::new()))
+            .collect();
+        for (state, alphabet, mut to_states, line) in parsed.transitions {
+            match (alphabet, to_states.len()) {
+                (_, 0) => {}
+                (None, _) => {
+                    return Err(TableParseError::Syntax {
+                        line,
+                        message: format!("epsilon transition from state {} in a DFA", state),
+                    })
+                }
+                (Some(alphabet), 1) => {
+                    transition_map
+                        .get_mut(&state)
+                        .unwrap()
+                        .insert(alphabet, to_states.pop().unwrap());
+                }
+                (Some(alphabet), count) => {
+                    return Err(TableParseError::Syntax {
+                        line,
+                        message: format!(
+                            "state {} has {} transitions for alphabet {} in a DFA",
+                            state, count, alphabet
+                        ),
+                    })
+                }
+            }
+        }
+        Ok(DFA::try_from_formal_strict(
+            parsed.states,
+            parsed.alphabets,
+            parsed.start_state,
+            parsed.accepted_states,
+            transition_map,
+        )?)
+    }
+}
+
+struct ParsedTable {
+    states: HashSet<String>,
+    alphabets: HashSet<String>,
+    start_state: String,
+    accepted_states: HashSet<String>,
+    /// `(from, alphabet, to, line)`, where `None` is epsilon.
+    transitions: Vec<(String, Option<String>, Vec<String>, usize)>,
+}
+
+impl ParsedTable {
+    fn parse(table: &str) -> Result<Self> {
+        let mut lines = table
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let (header_line, header) = match lines.next() {
+            None => {
+                return Err(TableParseError::Syntax {
+                    line: table.lines().count().max(1),
+                    message: "missing header row".to_string(),
+                })
+            }
+            Some((line_number, line)) => (
+                line_number,
+                line.split_whitespace()
+                    .map(|alphabet| {
+                        if alphabet == EPSILON {
+                            None
+                        } else {
+                            Some(alphabet.to_string())
+                        }
+                    })
+                    .collect::<Vec<Option<String>>>(),
+            ),
+        };
+        let alphabets: HashSet<String> = header.iter().flatten().cloned().collect();
+        if alphabets.len() != header.iter().flatten().count() {
+            return Err(TableParseError::Syntax {
+                line: header_line,
+                message: "duplicate alphabet in header row".to_string(),
+            });
+        }
+
+        let mut states = HashSet::new();
+        let mut start_states = vec![];
+        let mut accepted_states = HashSet::new();
+        let mut transitions = vec![];
+        for (line_number, line) in lines {
+            let mut tokens = tokenize_row(line, line_number)?;
+            let mut is_start = false;
+            let mut is_accepted = false;
+            while let Some(token) = tokens.first() {
+                match token.as_str() {
+                    "->" => is_start = true,
+                    "*" => is_accepted = true,
+                    "->*" | "*->" => {
+                        is_start = true;
+                        is_accepted = true;
+                    }
+                    _ => break,
+                }
+                tokens.remove(0);
+            }
+            if tokens.len() != header.len() + 1 {
+                return Err(TableParseError::Syntax {
+                    line: line_number,
+                    message: format!(
+                        "expected a state and {} cells, found {} columns",
+                        header.len(),
+                        tokens.len()
+                    ),
+                });
+            }
+            let state = tokens.remove(0);
+            if !states.insert(state.clone()) {
+                return Err(TableParseError::Syntax {
+                    line: line_number,
+                    message: format!("duplicate row for state {}", state),
+                });
+            }
+            if is_start {
+                start_states.push(state.clone());
+            }
+            if is_accepted {
+                accepted_states.insert(state.clone());
+            }
+            for (alphabet, cell) in header.iter().zip(tokens) {
+                let to_states = match cell.strip_prefix('{') {
+                    None => vec![cell],
+                    Some(cell) => cell
+                        .trim_end_matches('}')
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|to_state| !to_state.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                };
+                transitions.push((state.clone(), alphabet.clone(), to_states, line_number));
+            }
+        }
+        if start_states.len() != 1 {
+            return Err(TableParseError::Syntax {
+                line: header_line,
+                message: format!(
+                    "expected exactly one start state marked with {}, found {}",
+                    START_MARKER,
+                    start_states.len()
+                ),
+            });
+        }
+        Ok(Self {
+            states,
+            alphabets,
+            start_state: start_states.pop().unwrap(),
+            accepted_states,
+            transitions,
+        })
+    }
+}
+
+/// Splits a row at whitespace, keeping sets like `{a, b}` together.
+fn tokenize_row(line: &str, line_number: usize) -> Result<Vec<String>> {
+    let mut tokens = vec![];
+    let mut cur = String::new();
+    let mut in_set = false;
+    for c in line.chars() {
+        match c {
+            '{' if !in_set && cur.is_empty() => {
+                in_set = true;
+                cur.push(c);
+            }
+            '}' if in_set => {
+                in_set = false;
+                cur.push(c);
+            }
+            c if c.is_whitespace() && !in_set => {
+                if !cur.is_empty() {
+                    tokens.push(cur.clone());
+                    cur.clear();
+                }
+            }
+            '{' | '}' => {
+                return Err(TableParseError::Syntax {
+                    line: line_number,
+                    message: format!("unexpected '{}'", c),
+                })
+            }
+            c => cur.push(c),
+        }
+    }
+    if in_set {
+        return Err(TableParseError::Syntax {
+            line: line_number,
+            message: "unclosed '{'".to_string(),
+        });
+    }
+    if !cur.is_empty() {
+        tokens.push(cur);
+    }
+    Ok(tokens)
+}
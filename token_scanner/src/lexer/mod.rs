@@ -0,0 +1,17 @@
+pub mod modes;
+pub mod pattern;
+pub mod recovery;
+pub mod scanner;
+pub mod source_map;
+pub mod spec;
+pub mod spec_file;
+pub mod token;
+
+pub use modes::{ModalLexerDFA, ModalLexerSpec, UnknownModeError};
+pub use pattern::Pattern;
+pub use recovery::{Diagnostic, RecoveringLexer, RecoveryStrategy, TokenKind};
+pub use scanner::{Lexer, Located};
+pub use source_map::{Location, Position, SourceMap};
+pub use spec::{Action, LexerDFA, LexerSpec, Rule};
+pub use spec_file::SpecFileError;
+pub use token::{LexError, Span, Token};
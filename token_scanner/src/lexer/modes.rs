@@ -0,0 +1,163 @@
+use crate::lexer::recovery::{RecoveringLexer, RecoveryStrategy};
+use crate::lexer::scanner::Lexer;
+use crate::lexer::spec::{Action, LexerDFA, LexerSpec, ModeAction, Rule};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result};
+
+/// An action of a rule refers to a mode that does not exist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownModeError {
+    pub mode: String,
+    /// Mode of the rule with the action.
+    pub in_mode: String,
+    pub rule: usize,
+}
+
+impl Display for UnknownModeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "rule {} of mode {:?} refers to unknown mode {:?}",
+            self.rule, self.in_mode, self.mode
+        )
+    }
+}
+
+impl Error for UnknownModeError {}
+
+/// Named lexer modes, like the start conditions of flex. Every mode has its own rules, and the
+/// actions of rules push, pop or switch the mode the next token is read in. The first mode added
+/// is the initial mode.
+///
+/// ```
+/// use token_scanner::lexer::{Action, ModalLexerSpec, Pattern};
+/// use token_scanner::unicode::char_class::CharClass;
+///
+/// // Nested block comments.
+/// let mut spec = ModalLexerSpec::new();
+/// spec.mode("code")
+///     .rule_with_action("OPEN", Pattern::literal("/*"), Action::Push("comment".to_string()))
+///     .rule("CODE", Pattern::class(CharClass::any()));
+/// spec.mode("comment")
+///     .rule_with_action("OPEN", Pattern::literal("/*"), Action::Push("comment".to_string()))
+///     .rule_with_action("CLOSE", Pattern::literal("*/"), Action::Pop)
+///     .rule("TEXT", Pattern::class(CharClass::any()));
+/// let lexer = spec.compile().unwrap();
+/// let kinds: Vec<&str> = lexer
+///     .lex("/*/**/*/x")
+///     .map(|token| token.unwrap().kind)
+///     .collect();
+/// assert_eq!(kinds, vec!["OPEN", "OPEN", "CLOSE", "CLOSE", "CODE"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ModalLexerSpec<K> {
+    modes: Vec<(String, LexerSpec<K>)>,
+}
+
+impl<K> Default for ModalLexerSpec<K> {
+    fn default() -> Self {
+        Self { modes: vec![] }
+    }
+}
+
+impl<K> ModalLexerSpec<K>
+where
+    K: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the rules of mode `name`, adding the mode if it does not exist yet.
+    pub fn mode(&mut self, name: &str) -> &mut LexerSpec<K> {
+        let idx = match self.modes.iter().position(|(mode, _)| mode == name) {
+            Some(idx) => idx,
+            None => {
+                self.modes.push((name.to_string(), LexerSpec::new()));
+                self.modes.len() - 1
+            }
+        };
+        &mut self.modes[idx].1
+    }
+
+    pub fn modes(&self) -> &[(String, LexerSpec<K>)] {
+        &self.modes
+    }
+
+    /// Compiles every mode into its own DFA.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no mode was added.
+    pub fn compile(&self) -> std::result::Result<ModalLexerDFA<K>, UnknownModeError> {
+        assert!(
+            !self.modes.is_empty(),
+            "a modal lexer needs at least one mode"
+        );
+        let mode_index = |in_mode: &str, rule: usize, mode: &str| {
+            self.modes
+                .iter()
+                .position(|(name, _)| name == mode)
+                .ok_or_else(|| UnknownModeError {
+                    mode: mode.to_string(),
+                    in_mode: in_mode.to_string(),
+                    rule,
+                })
+        };
+        let mut names = vec![];
+        let mut dfas = vec![];
+        for (name, spec) in self.modes.iter() {
+            let mut mode_actions = vec![];
+            for (rule, Rule { action, .. }) in spec.rules().iter().enumerate() {
+                mode_actions.push(match action {
+                    None => None,
+                    Some(Action::Push(mode)) => {
+                        Some(ModeAction::Push(mode_index(name, rule, mode)?))
+                    }
+                    Some(Action::Pop) => Some(ModeAction::Pop),
+                    Some(Action::Switch(mode)) => {
+                        Some(ModeAction::Switch(mode_index(name, rule, mode)?))
+                    }
+                });
+            }
+            names.push(name.clone());
+            dfas.push(spec.compile_with_actions(mode_actions));
+        }
+        Ok(ModalLexerDFA { names, dfas })
+    }
+}
+
+/// The DFAs of the modes of a `ModalLexerSpec`, in the order the modes were added.
+#[derive(Clone, Debug)]
+pub struct ModalLexerDFA<K> {
+    names: Vec<String>,
+    dfas: Vec<LexerDFA<K>>,
+}
+
+impl<K> ModalLexerDFA<K> {
+    pub fn lex<'a>(&'a self, input: &'a str) -> Lexer<'a, K> {
+        Lexer::with_modes(&self.dfas, input)
+    }
+
+    pub fn lex_with_recovery<'a>(
+        &'a self,
+        input: &'a str,
+        strategy: RecoveryStrategy,
+    ) -> RecoveringLexer<'a, K> {
+        RecoveringLexer::with_modes(&self.dfas, input, strategy)
+    }
+
+    pub fn mode_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// DFAs by mode index.
+    pub fn modes(&self) -> &[LexerDFA<K>] {
+        &self.dfas
+    }
+
+    pub fn mode(&self, name: &str) -> Option<&LexerDFA<K>> {
+        let idx = self.names.iter().position(|mode| mode == name)?;
+        Some(&self.dfas[idx])
+    }
+}
@@ -0,0 +1,292 @@
+use crate::unicode::char_class::{CharClass, CharSet};
+use crate::{FiniteAutomaton, NFATransitionMap, StateIdentifier, NFA};
+use maplit::hashset;
+use std::collections::HashMap;
+
+/// The language of a lexer rule, compiled into an NFA by Thompson's construction.
+///
+/// ```
+/// use token_scanner::lexer::pattern::Pattern;
+/// use token_scanner::unicode::char_automaton::CharAutomaton;
+/// use token_scanner::unicode::char_class::{CharClass, CharRange};
+///
+/// let letter = Pattern::class(CharClass::from(CharRange::new('a', 'z')));
+/// let digit = Pattern::class(CharClass::from(CharRange::new('0', '9')));
+/// let identifier = Pattern::concat(vec![
+///     letter.clone(),
+///     Pattern::alternation(vec![letter, digit]).star(),
+/// ]);
+/// let nfa = identifier.to_nfa();
+/// assert!(nfa.accept_chars("x86".chars()));
+/// assert!(!nfa.accept_chars("86".chars()));
+/// ```
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    /// The characters of the string in order, the empty string matches only the empty string.
+    Literal(String),
+    /// Any single character of the class.
+    Class(CharClass),
+    Concat(Vec<Pattern>),
+    Alternation(Vec<Pattern>),
+    /// Between `min` and `max` repetitions, unbounded if `max` is `None`.
+    Repeat {
+        pattern: Box<Pattern>,
+        min: usize,
+        max: Option<usize>,
+    },
+    /// The language of an existing automaton.
+    Automaton(NFA<CharClass, usize>),
+}
+
+impl Pattern {
+    pub fn literal(text: &str) -> Self {
+        Pattern::Literal(text.to_string())
+    }
+
+    pub fn class(class: CharClass) -> Self {
+        Pattern::Class(class)
+    }
+
+    pub fn concat(patterns: Vec<Pattern>) -> Self {
+        Pattern::Concat(patterns)
+    }
+
+    pub fn alternation(patterns: Vec<Pattern>) -> Self {
+        Pattern::Alternation(patterns)
+    }
+
+    /// The language of `fa`, whose alphabets are sets of characters.
+    pub fn automaton<T, U, F>(fa: &F) -> Self
+    where
+        T: CharSet,
+        U: StateIdentifier,
+        F: FiniteAutomaton<T, U> + ?Sized,
+    {
+        let states: Vec<U> = fa.states().iter().cloned().collect();
+        let state_to_idx: HashMap<&U, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (state, idx))
+            .collect();
+        let mut transition_map: NFATransitionMap<CharClass, usize> = HashMap::new();
+        for state in states.iter() {
+            let mut alphabets: Vec<Option<&T>> = fa.alphabets().iter().map(Some).collect();
+            alphabets.push(None);
+            for alphabet in alphabets {
+                let to_states = fa.transition(state.clone(), alphabet.cloned());
+                if to_states.is_empty() {
+                    continue;
+                }
+                transition_map
+                    .entry(state_to_idx[state])
+                    .or_default()
+                    .entry(alphabet.map(CharSet::to_char_class))
+                    .or_default()
+                    .extend(to_states.iter().map(|to_state| state_to_idx[to_state]));
+            }
+        }
+        Pattern::Automaton(NFA::from_formal(
+            (0..states.len()).collect(),
+            fa.alphabets().iter().map(CharSet::to_char_class).collect(),
+            state_to_idx[&fa.start_state()],
+            fa.accepted_states()
+                .iter()
+                .map(|state| state_to_idx[state])
+                .collect(),
+            transition_map,
+        ))
+    }
+
+    /// Between `min` and `max` repetitions, unbounded if `max` is `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is below `min`.
+    pub fn repeat(self, min: usize, max: Option<usize>) -> Self {
+        if let Some(max) = max {
+            assert!(
+                min <= max,
+                "Invalid repetition {{{},{}}}, the maximum is below the minimum.",
+                min,
+                max
+            );
+        }
+        Pattern::Repeat {
+            pattern: Box::new(self),
+            min,
+            max,
+        }
+    }
+
+    /// Zero or more repetitions.
+    pub fn star(self) -> Self {
+        self.repeat(0, None)
+    }
+
+    /// One or more repetitions.
+    pub fn plus(self) -> Self {
+        self.repeat(1, None)
+    }
+
+    /// Zero or one repetition.
+    pub fn optional(self) -> Self {
+        self.repeat(0, Some(1))
+    }
+
+    /// Compiles the pattern into an NFA with states `0..n`.
+    pub fn to_nfa(&self) -> NFA<CharClass, usize> {
+        let mut builder = ThompsonBuilder::default();
+        let (start_state, accepted_state) = builder.build(self);
+        builder.into_nfa(start_state, accepted_state)
+    }
+}
+
+/// Builds NFAs out of fragments with a single start and a single accepted state.
+#[derive(Default)]
+pub(crate) struct ThompsonBuilder {
+    state_count: usize,
+    transition_map: NFATransitionMap<CharClass, usize>,
+}
+
+impl ThompsonBuilder {
+    pub(crate) fn new_state(&mut self) -> usize {
+        self.state_count += 1;
+        self.state_count - 1
+    }
+
+    pub(crate) fn add_transition(&mut self, from: usize, alphabet: Option<CharClass>, to: usize) {
+        self.transition_map
+            .entry(from)
+            .or_default()
+            .entry(alphabet)
+            .or_default()
+            .insert(to);
+    }
+
+    /// Adds the fragment of `pattern`, returning its start and accepted state.
+    pub(crate) fn build(&mut self, pattern: &Pattern) -> (usize, usize) {
+        match pattern {
+            Pattern::Literal(text) => {
+                let start_state = self.new_state();
+                let mut cur_state = start_state;
+                for c in text.chars() {
+                    let next_state = self.new_state();
+                    self.add_transition(cur_state, Some(CharClass::from(c)), next_state);
+                    cur_state = next_state;
+                }
+                (start_state, cur_state)
+            }
+            Pattern::Class(class) => {
+                let start_state = self.new_state();
+                let accepted_state = self.new_state();
+                self.add_transition(start_state, Some(class.clone()), accepted_state);
+                (start_state, accepted_state)
+            }
+            Pattern::Concat(patterns) => {
+                let start_state = self.new_state();
+                let mut cur_state = start_state;
+                for pattern in patterns {
+                    let (sub_start, sub_accepted) = self.build(pattern);
+                    self.add_transition(cur_state, None, sub_start);
+                    cur_state = sub_accepted;
+                }
+                (start_state, cur_state)
+            }
+            Pattern::Alternation(patterns) => {
+                let start_state = self.new_state();
+                let accepted_state = self.new_state();
+                for pattern in patterns {
+                    let (sub_start, sub_accepted) = self.build(pattern);
+                    self.add_transition(start_state, None, sub_start);
+                    self.add_transition(sub_accepted, None, accepted_state);
+                }
+                (start_state, accepted_state)
+            }
+            Pattern::Repeat { pattern, min, max } => {
+                let start_state = self.new_state();
+                let mut cur_state = start_state;
+                for _ in 0..*min {
+                    let (sub_start, sub_accepted) = self.build(pattern);
+                    self.add_transition(cur_state, None, sub_start);
+                    cur_state = sub_accepted;
+                }
+                match max {
+                    None => {
+                        let (sub_start, sub_accepted) = self.build(pattern);
+                        let accepted_state = self.new_state();
+                        self.add_transition(cur_state, None, sub_start);
+                        self.add_transition(cur_state, None, accepted_state);
+                        self.add_transition(sub_accepted, None, sub_start);
+                        self.add_transition(sub_accepted, None, accepted_state);
+                        (start_state, accepted_state)
+                    }
+                    Some(max) => {
+                        let accepted_state = self.new_state();
+                        for _ in *min..*max {
+                            let (sub_start, sub_accepted) = self.build(pattern);
+                            self.add_transition(cur_state, None, sub_start);
+                            self.add_transition(cur_state, None, accepted_state);
+                            cur_state = sub_accepted;
+                        }
+                        self.add_transition(cur_state, None, accepted_state);
+                        (start_state, accepted_state)
+                    }
+                }
+            }
+            Pattern::Automaton(nfa) => {
+                // The states of the automaton may have any numbers.
+                let mut states: Vec<usize> = nfa.states().iter().cloned().collect();
+                states.sort_unstable();
+                let state_to_new: HashMap<usize, usize> = states
+                    .into_iter()
+                    .map(|state| (state, self.new_state()))
+                    .collect();
+                for (state, map) in nfa.transition_map() {
+                    for (alphabet, to_states) in map {
+                        for to_state in to_states {
+                            self.add_transition(
+                                state_to_new[state],
+                                alphabet.clone(),
+                                state_to_new[to_state],
+                            );
+                        }
+                    }
+                }
+                let accepted_state = self.new_state();
+                for state in nfa.accepted_states() {
+                    self.add_transition(state_to_new[state], None, accepted_state);
+                }
+                (state_to_new[&nfa.start_state()], accepted_state)
+            }
+        }
+    }
+
+    pub(crate) fn into_nfa(
+        self,
+        start_state: usize,
+        accepted_state: usize,
+    ) -> NFA<CharClass, usize> {
+        self.into_nfa_with_accepted_states(start_state, hashset! {accepted_state})
+    }
+
+    pub(crate) fn into_nfa_with_accepted_states(
+        self,
+        start_state: usize,
+        accepted_states: std::collections::HashSet<usize>,
+    ) -> NFA<CharClass, usize> {
+        let alphabets = self
+            .transition_map
+            .values()
+            .flat_map(|map| map.keys())
+            .flatten()
+            .cloned()
+            .collect();
+        NFA::from_formal(
+            (0..self.state_count).collect(),
+            alphabets,
+            start_state,
+            accepted_states,
+            self.transition_map,
+        )
+    }
+}
@@ -0,0 +1,181 @@
+use crate::lexer::scanner::ModeStack;
+use crate::lexer::source_map::SourceMap;
+use crate::lexer::spec::LexerDFA;
+use crate::lexer::token::{Span, Token};
+use std::fmt::{Display, Formatter, Result};
+
+/// How far a lexer skips when no rule matches at a position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RecoveryStrategy {
+    /// Skips the character no rule matches at.
+    SkipChar,
+    /// Skips to the next whitespace character, or one character if it is whitespace itself.
+    SkipToWhitespace,
+    /// Skips to the next character that a rule can start with.
+    #[default]
+    PanicMode,
+}
+
+/// Kind of the tokens of a `RecoveringLexer`: the kind of a rule, or the error token covering
+/// characters skipped by recovery.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenKind<K> {
+    Rule(K),
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic with the source line underlined at its span.
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        source_map.render_snippet(self.span, &self.message)
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} at {}", self.message, self.span)
+    }
+}
+
+impl<K> LexerDFA<K> {
+    /// Returns the end of the error span starting at `position`, where no rule matches.
+    pub(crate) fn recover(
+        &self,
+        input: &str,
+        position: usize,
+        strategy: RecoveryStrategy,
+    ) -> usize {
+        let rest = &input[position..];
+        let first_len = rest.chars().next().map_or(0, char::len_utf8);
+        let skipped = match strategy {
+            RecoveryStrategy::SkipChar => first_len,
+            RecoveryStrategy::SkipToWhitespace => rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| c.is_whitespace())
+                .map_or(rest.len(), |(offset, _)| offset),
+            RecoveryStrategy::PanicMode => rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| self.can_start_with(*c))
+                .map_or(rest.len(), |(offset, _)| offset),
+        };
+        position + skipped.max(first_len)
+    }
+}
+
+/// Splits an input into tokens by maximal munch like `Lexer`, but emits an error token over the
+/// characters where no rule matches and continues after them, collecting a diagnostic for every
+/// error token.
+///
+/// ```
+/// use token_scanner::lexer::{LexerSpec, Pattern, RecoveryStrategy, TokenKind};
+///
+/// let mut spec = LexerSpec::new();
+/// spec.rule("A", Pattern::literal("a").plus()).rule("SPACE", Pattern::literal(" "));
+/// let lexer = spec.compile();
+/// let mut tokens = lexer.lex_with_recovery("a x!y a", RecoveryStrategy::SkipToWhitespace);
+/// let kinds: Vec<TokenKind<&str>> = tokens.by_ref().map(|token| token.kind).collect();
+/// assert_eq!(
+///     kinds,
+///     vec![
+///         TokenKind::Rule("A"),
+///         TokenKind::Rule("SPACE"),
+///         TokenKind::Error,
+///         TokenKind::Rule("SPACE"),
+///         TokenKind::Rule("A"),
+///     ]
+/// );
+/// assert_eq!(tokens.diagnostics()[0].to_string(), "unexpected \"x!y\" at 2..5");
+/// ```
+pub struct RecoveringLexer<'a, K> {
+    modes: ModeStack<'a, K>,
+    input: &'a str,
+    position: usize,
+    strategy: RecoveryStrategy,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a, K> RecoveringLexer<'a, K> {
+    pub fn new(dfa: &'a LexerDFA<K>, input: &'a str, strategy: RecoveryStrategy) -> Self {
+        Self::with_modes(std::slice::from_ref(dfa), input, strategy)
+    }
+
+    pub(crate) fn with_modes(
+        modes: &'a [LexerDFA<K>],
+        input: &'a str,
+        strategy: RecoveryStrategy,
+    ) -> Self {
+        Self {
+            modes: ModeStack::new(modes),
+            input,
+            position: 0,
+            strategy,
+            diagnostics: vec![],
+        }
+    }
+
+    /// Index of the current mode, `0` being the initial mode.
+    pub fn mode(&self) -> usize {
+        self.modes.mode()
+    }
+
+    /// Diagnostics of the error tokens emitted so far.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
+
+impl<'a, K> Iterator for RecoveringLexer<'a, K>
+where
+    K: Clone,
+{
+    type Item = Token<'a, TokenKind<K>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.position == self.input.len() {
+                return None;
+            }
+            let dfa = self.modes.dfa();
+            let (kind, end) = match dfa.longest_match(&self.input[self.position..]) {
+                Some((rule, len)) if dfa.is_skipped(rule) => {
+                    self.modes.apply(rule);
+                    self.position += len;
+                    continue;
+                }
+                Some((rule, len)) => {
+                    self.modes.apply(rule);
+                    (
+                        TokenKind::Rule(dfa.kinds()[rule].clone()),
+                        self.position + len,
+                    )
+                }
+                None => (
+                    TokenKind::Error,
+                    dfa.recover(self.input, self.position, self.strategy),
+                ),
+            };
+            let span = Span::new(self.position, end);
+            let lexeme = &self.input[span.start..span.end];
+            if let TokenKind::Error = kind {
+                self.diagnostics.push(Diagnostic {
+                    span,
+                    message: format!("unexpected {:?}", lexeme),
+                });
+            }
+            self.position = end;
+            return Some(Token { kind, lexeme, span });
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::lexer::source_map::{Location, PositionCursor};
+use crate::lexer::spec::{LexerDFA, ModeAction};
+use crate::lexer::token::{LexError, Span, Token};
+
+/// The modes of a lexer, with the stack of modes entered so far. A lexer of a single DFA has one
+/// mode.
+pub(crate) struct ModeStack<'a, K> {
+    modes: &'a [LexerDFA<K>],
+    stack: Vec<usize>,
+}
+
+impl<'a, K> ModeStack<'a, K> {
+    pub(crate) fn new(modes: &'a [LexerDFA<K>]) -> Self {
+        Self {
+            modes,
+            stack: vec![0],
+        }
+    }
+
+    pub(crate) fn mode(&self) -> usize {
+        *self.stack.last().unwrap()
+    }
+
+    pub(crate) fn dfa(&self) -> &'a LexerDFA<K> {
+        &self.modes[self.mode()]
+    }
+
+    /// Applies the action of `rule` of the current mode.
+    pub(crate) fn apply(&mut self, rule: usize) {
+        match self.dfa().mode_actions[rule] {
+            Some(ModeAction::Push(mode)) => self.stack.push(mode),
+            Some(ModeAction::Pop) if self.stack.len() > 1 => {
+                self.stack.pop();
+            }
+            Some(ModeAction::Switch(mode)) => *self.stack.last_mut().unwrap() = mode,
+            // Popping the initial mode stays in it.
+            Some(ModeAction::Pop) | None => {}
+        }
+    }
+}
+
+/// Splits an input into tokens by maximal munch, leaving out the tokens of skip rules. Iteration
+/// stops after the first error.
+pub struct Lexer<'a, K> {
+    modes: ModeStack<'a, K>,
+    input: &'a str,
+    position: usize,
+    failed: bool,
+}
+
+impl<'a, K> Lexer<'a, K> {
+    pub fn new(dfa: &'a LexerDFA<K>, input: &'a str) -> Self {
+        Self::with_modes(std::slice::from_ref(dfa), input)
+    }
+
+    pub(crate) fn with_modes(modes: &'a [LexerDFA<K>], input: &'a str) -> Self {
+        Self {
+            modes: ModeStack::new(modes),
+            input,
+            position: 0,
+            failed: false,
+        }
+    }
+
+    /// Index of the current mode, `0` being the initial mode.
+    pub fn mode(&self) -> usize {
+        self.modes.mode()
+    }
+
+    /// Byte offset of the next token.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Pairs every token with the lines and columns of its span.
+    ///
+    /// ```
+    /// use token_scanner::lexer::{LexerSpec, Pattern};
+    ///
+    /// let mut spec = LexerSpec::new();
+    /// spec.rule("WORD", Pattern::literal("ab")).rule("NEWLINE", Pattern::literal("\n"));
+    /// let lexer = spec.compile();
+    /// let (token, location) = lexer.lex("ab\nab").located().last().unwrap().unwrap();
+    /// assert_eq!(token.lexeme, "ab");
+    /// assert_eq!((location.start.line, location.start.column), (2, 1));
+    /// ```
+    pub fn located(self) -> Located<'a, K> {
+        Located {
+            cursor: PositionCursor::new(self.input),
+            lexer: self,
+        }
+    }
+}
+
+impl<'a, K> Iterator for Lexer<'a, K>
+where
+    K: Clone,
+{
+    type Item = Result<Token<'a, K>, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.failed || self.position == self.input.len() {
+                return None;
+            }
+            let dfa = self.modes.dfa();
+            match dfa.longest_match(&self.input[self.position..]) {
+                None => {
+                    self.failed = true;
+                    return Some(Err(LexError {
+                        position: self.position,
+                    }));
+                }
+                Some((rule, len)) => {
+                    let span = Span::new(self.position, self.position + len);
+                    self.position = span.end;
+                    self.modes.apply(rule);
+                    if !dfa.is_skipped(rule) {
+                        return Some(Ok(Token {
+                            kind: dfa.kinds()[rule].clone(),
+                            lexeme: &self.input[span.start..span.end],
+                            span,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the tokens of a `Lexer` with their locations, returned by `Lexer::located`.
+pub struct Located<'a, K> {
+    lexer: Lexer<'a, K>,
+    cursor: PositionCursor<'a>,
+}
+
+impl<'a, K> Iterator for Located<'a, K>
+where
+    K: Clone,
+{
+    type Item = Result<(Token<'a, K>, Location), LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.lexer.next()?.map(|token| {
+            let location = Location {
+                start: self.cursor.advance_to(token.span.start),
+                end: self.cursor.advance_to(token.span.end),
+            };
+            (token, location)
+        }))
+    }
+}
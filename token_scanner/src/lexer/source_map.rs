@@ -0,0 +1,185 @@
+use crate::lexer::token::Span;
+use std::fmt::{Display, Formatter, Result, Write};
+
+/// A location in a source. Lines and columns start at 1, and columns count from the start of the
+/// line in characters, in UTF-8 bytes, or in UTF-16 code units, as editors and protocols differ.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Byte offset from the start of the source.
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub utf8_column: usize,
+    pub utf16_column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The positions of the start and the end of a span.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Maps byte offsets of a source to lines and columns. Lines end at `\n`, and a `\r` before it is
+/// not part of the line.
+///
+/// ```
+/// use token_scanner::lexer::{SourceMap, Span};
+///
+/// let map = SourceMap::new("let x = 1;\nlet y = @;\n");
+/// let position = map.position(19);
+/// assert_eq!((position.line, position.column), (2, 9));
+/// assert_eq!(
+///     map.render_snippet(Span::new(19, 20), "unexpected character"),
+///     "error: unexpected character\n --> 2:9\n  |\n2 | let y = @;\n  |         ^\n"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct SourceMap<'a> {
+    source: &'a str,
+    /// Byte offset of the start of every line.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns line `line`, starting at 1, without its line break.
+    pub fn line(&self, line: usize) -> &'a str {
+        let start = self.line_starts[line - 1];
+        let end = match self.line_starts.get(line) {
+            Some(next_start) => next_start - 1,
+            None => self.source.len(),
+        };
+        let line = &self.source[start..end];
+        line.strip_suffix('\r').unwrap_or(line)
+    }
+
+    /// Returns the position of byte `offset`, which may be the length of the source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is past the end of the source or not at a character boundary.
+    pub fn position(&self, offset: usize) -> Position {
+        assert!(
+            self.source.is_char_boundary(offset),
+            "offset {} is not a character boundary",
+            offset
+        );
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(idx) => idx,
+            Err(idx) => idx - 1,
+        };
+        let before = &self.source[self.line_starts[line]..offset];
+        Position {
+            offset,
+            line: line + 1,
+            column: before.chars().count() + 1,
+            utf8_column: before.len() + 1,
+            utf16_column: before.encode_utf16().count() + 1,
+        }
+    }
+
+    pub fn location(&self, span: Span) -> Location {
+        Location {
+            start: self.position(span.start),
+            end: self.position(span.end),
+        }
+    }
+
+    /// Renders `message` with the line where `span` starts, underlining the span with carets. A
+    /// span over several lines is underlined to the end of its first line, and an empty span gets
+    /// a single caret.
+    pub fn render_snippet(&self, span: Span, message: &str) -> String {
+        let location = self.location(span);
+        let line = self.line(location.start.line);
+        let line_number = location.start.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        // A span may start or end at the `\r` of a line break.
+        let start = (location.start.utf8_column - 1).min(line.len());
+        let end = if location.end.line == location.start.line {
+            (location.end.utf8_column - 1).min(line.len())
+        } else {
+            line.len()
+        };
+        let prefix = &line[..start];
+        let underlined = &line[start..end];
+        // Tabs are kept, so the carets line up however wide tabs are displayed.
+        let padding: String = prefix
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let carets = "^".repeat(underlined.chars().count().max(1));
+
+        let mut res = String::new();
+        writeln!(res, "error: {}", message).unwrap();
+        writeln!(res, "{}--> {}", gutter, location.start).unwrap();
+        writeln!(res, "{} |", gutter).unwrap();
+        writeln!(res, "{} | {}", line_number, line).unwrap();
+        writeln!(res, "{} | {}{}", gutter, padding, carets).unwrap();
+        res
+    }
+}
+
+/// Computes positions at increasing offsets of a source by scanning forward from the last one, so
+/// locating all lexemes of a source takes time linear in its length.
+#[derive(Clone, Debug)]
+pub(crate) struct PositionCursor<'a> {
+    source: &'a str,
+    position: Position,
+}
+
+impl<'a> PositionCursor<'a> {
+    pub(crate) fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            position: Position {
+                offset: 0,
+                line: 1,
+                column: 1,
+                utf8_column: 1,
+                utf16_column: 1,
+            },
+        }
+    }
+
+    /// Returns the position of `offset`, which must not be before the last offset.
+    pub(crate) fn advance_to(&mut self, offset: usize) -> Position {
+        for c in self.source[self.position.offset..offset].chars() {
+            if c == '\n' {
+                self.position.line += 1;
+                self.position.column = 1;
+                self.position.utf8_column = 1;
+                self.position.utf16_column = 1;
+            } else {
+                self.position.column += 1;
+                self.position.utf8_column += c.len_utf8();
+                self.position.utf16_column += c.len_utf16();
+            }
+        }
+        self.position.offset = offset;
+        self.position
+    }
+}
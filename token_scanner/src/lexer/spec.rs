@@ -0,0 +1,339 @@
+use crate::automaton::tagged::TaggedNFA;
+use crate::custom_traits::finite_automaton::co_reachable_states;
+use crate::lexer::pattern::{Pattern, ThompsonBuilder};
+use crate::lexer::recovery::{RecoveringLexer, RecoveryStrategy};
+use crate::lexer::scanner::Lexer;
+use crate::FiniteAutomaton;
+use std::collections::HashMap;
+
+/// Marks the transitions into states from which no rule can match anymore.
+pub(crate) const DEAD_STATE: u32 = u32::MAX;
+
+/// Marks characters that no rule reads.
+const NO_CLASS: u32 = u32::MAX;
+
+/// An ordered list of token rules. The lexer takes the longest match at every position, and the
+/// earliest rule among the rules matching that longest lexeme.
+///
+/// ```
+/// use token_scanner::lexer::pattern::Pattern;
+/// use token_scanner::lexer::LexerSpec;
+/// use token_scanner::unicode::char_class::{CharClass, CharRange};
+///
+/// let letters = Pattern::class(CharClass::from(CharRange::new('a', 'z'))).plus();
+/// let mut spec = LexerSpec::new();
+/// spec.rule("IF", Pattern::literal("if"))
+///     .rule("IDENT", letters)
+///     .rule("SPACE", Pattern::literal(" "));
+/// let lexer = spec.compile();
+/// let kinds: Vec<&str> = lexer
+///     .lex("if iffy")
+///     .map(|token| token.unwrap().kind)
+///     .collect();
+/// assert_eq!(kinds, vec!["IF", "SPACE", "IDENT"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LexerSpec<K> {
+    rules: Vec<Rule<K>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rule<K> {
+    pub kind: K,
+    pub pattern: Pattern,
+    /// Mode change after a token of the rule, see `ModalLexerSpec`.
+    pub action: Option<Action>,
+    /// Tokens of skip rules, like whitespace and comments, are consumed but not emitted.
+    pub skip: bool,
+}
+
+/// Changes the mode of a lexer after a token, where modes form a stack.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Enters the mode, returning to the current mode on `Pop`.
+    Push(String),
+    /// Returns to the mode before the last `Push`. Does nothing in the initial mode.
+    Pop,
+    /// Replaces the current mode.
+    Switch(String),
+}
+
+/// An `Action` with its mode resolved to an index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ModeAction {
+    Push(usize),
+    Pop,
+    Switch(usize),
+}
+
+impl<K> Default for LexerSpec<K> {
+    fn default() -> Self {
+        Self { rules: vec![] }
+    }
+}
+
+impl<K> LexerSpec<K>
+where
+    K: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule with a lower priority than all rules added before.
+    pub fn rule(&mut self, kind: K, pattern: Pattern) -> &mut Self {
+        self.add_rule(Rule {
+            kind,
+            pattern,
+            action: None,
+            skip: false,
+        })
+    }
+
+    /// Adds a rule like `rule`, whose tokens change the mode of the lexer.
+    pub fn rule_with_action(&mut self, kind: K, pattern: Pattern, action: Action) -> &mut Self {
+        self.add_rule(Rule {
+            kind,
+            pattern,
+            action: Some(action),
+            skip: false,
+        })
+    }
+
+    /// Adds a rule like `rule`, whose tokens are not emitted by the lexer.
+    pub fn skip_rule(&mut self, kind: K, pattern: Pattern) -> &mut Self {
+        self.add_rule(Rule {
+            kind,
+            pattern,
+            action: None,
+            skip: true,
+        })
+    }
+
+    pub fn add_rule(&mut self, rule: Rule<K>) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule<K>] {
+        &self.rules
+    }
+
+    /// Combines the patterns of all rules into one DFA, whose accepting states know the rule
+    /// they accept. Actions are ignored, as they refer to the modes of a `ModalLexerSpec`.
+    pub fn compile(&self) -> LexerDFA<K> {
+        self.compile_with_actions(vec![None; self.rules.len()])
+    }
+
+    pub(crate) fn compile_with_actions(
+        &self,
+        mode_actions: Vec<Option<ModeAction>>,
+    ) -> LexerDFA<K> {
+        let mut builder = ThompsonBuilder::default();
+        let start_state = builder.new_state();
+        let mut accepted_state_to_rule = HashMap::new();
+        for (rule, Rule { pattern, .. }) in self.rules.iter().enumerate() {
+            let (sub_start, sub_accepted) = builder.build(pattern);
+            builder.add_transition(start_state, None, sub_start);
+            accepted_state_to_rule.insert(sub_accepted, rule);
+        }
+        let nfa = builder
+            .into_nfa_with_accepted_states(
+                start_state,
+                accepted_state_to_rule.keys().cloned().collect(),
+            )
+            .split_ranges();
+        // The earliest rule accepted by any NFA state of a subset wins.
+        let (dfa, rules) = TaggedNFA::new(nfa, accepted_state_to_rule)
+            .unwrap()
+            .to_dfa()
+            .minimized()
+            .relabeled()
+            .into_parts();
+        let accepting: Vec<Option<usize>> = (0..dfa.states().len())
+            .map(|state| rules.get(&state).cloned())
+            .collect();
+        // The start state is always kept, so that it is state `0` of the compiled DFA.
+        let co_reachable = co_reachable_states(&dfa);
+        let live: Vec<bool> = (0..dfa.states().len())
+            .map(|state| state == dfa.start_state() || co_reachable.contains(&state))
+            .collect();
+
+        let classes = dfa.alphabet_classes();
+        let mut ranges: Vec<(char, char, u32)> = dfa
+            .alphabets()
+            .iter()
+            .map(|range| {
+                (
+                    range.start(),
+                    range.end(),
+                    classes.class(range).unwrap() as u32,
+                )
+            })
+            .collect();
+        ranges.sort_unstable();
+        let mut ascii_classes = vec![NO_CLASS; 128];
+        for (start, end, class) in ranges.iter() {
+            for c in (*start as u32)..=(*end as u32).min(127) {
+                ascii_classes[c as usize] = *class;
+            }
+        }
+
+        let new_states: Vec<u32> = live
+            .iter()
+            .scan(0, |next_state, live| {
+                let new_state = if *live { *next_state } else { DEAD_STATE };
+                *next_state += *live as u32;
+                Some(new_state)
+            })
+            .collect();
+        let mut transitions = vec![];
+        for state in (0..dfa.states().len()).filter(|state| live[*state]) {
+            let map = dfa.transition_map().get(&state).unwrap();
+            for class in 0..classes.len() {
+                transitions.push(new_states[*map.get(classes.representative(class)).unwrap()]);
+            }
+        }
+        let start_classes = transitions[..classes.len()]
+            .iter()
+            .map(|to_state| *to_state != DEAD_STATE)
+            .collect();
+        LexerDFA {
+            kinds: self.rules.iter().map(|rule| rule.kind.clone()).collect(),
+            skip: self.rules.iter().map(|rule| rule.skip).collect(),
+            mode_actions,
+            ranges,
+            ascii_classes,
+            class_count: classes.len(),
+            start_state: 0,
+            transitions,
+            start_classes,
+            accepting: accepting
+                .into_iter()
+                .enumerate()
+                .filter(|(state, _)| live[*state])
+                .map(|(_, rule)| rule)
+                .collect(),
+        }
+    }
+}
+
+/// The combined DFA of a `LexerSpec`, with states `0..n` starting at `0` and one transition per
+/// class of characters.
+#[derive(Clone, Debug)]
+pub struct LexerDFA<K> {
+    kinds: Vec<K>,
+    /// Skip rules by rule.
+    skip: Vec<bool>,
+    /// Mode changes by rule.
+    pub(crate) mode_actions: Vec<Option<ModeAction>>,
+    /// Disjoint character ranges with their class, sorted.
+    ranges: Vec<(char, char, u32)>,
+    ascii_classes: Vec<u32>,
+    class_count: usize,
+    start_state: u32,
+    /// `transitions[state * class_count + class]`
+    transitions: Vec<u32>,
+    /// Whether a rule can start with a character, by class.
+    start_classes: Vec<bool>,
+    /// The rule accepted by every state.
+    accepting: Vec<Option<usize>>,
+}
+
+impl<K> LexerDFA<K> {
+    pub fn lex<'a>(&'a self, input: &'a str) -> Lexer<'a, K> {
+        Lexer::new(self, input)
+    }
+
+    pub fn lex_with_recovery<'a>(
+        &'a self,
+        input: &'a str,
+        strategy: RecoveryStrategy,
+    ) -> RecoveringLexer<'a, K> {
+        RecoveringLexer::new(self, input, strategy)
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub fn class_count(&self) -> usize {
+        self.class_count
+    }
+
+    pub fn start_state(&self) -> u32 {
+        self.start_state
+    }
+
+    /// Token kinds by rule.
+    pub fn kinds(&self) -> &[K] {
+        &self.kinds
+    }
+
+    /// Whether the tokens of `rule` are skipped.
+    pub fn is_skipped(&self, rule: usize) -> bool {
+        self.skip[rule]
+    }
+
+    /// Returns the rule whose token ends in `state`, if any.
+    pub fn accepting_rule(&self, state: u32) -> Option<usize> {
+        self.accepting[state as usize]
+    }
+
+    pub fn class(&self, c: char) -> Option<u32> {
+        let class = match self.ascii_classes.get(c as usize) {
+            Some(class) => *class,
+            None => match self.ranges.binary_search_by(|(start, end, _)| {
+                if *end < c {
+                    std::cmp::Ordering::Less
+                } else if *start > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            }) {
+                Ok(idx) => self.ranges[idx].2,
+                Err(_) => NO_CLASS,
+            },
+        };
+        if class == NO_CLASS {
+            None
+        } else {
+            Some(class)
+        }
+    }
+
+    /// Returns the next state, or `None` if no rule can match after reading `c`.
+    pub fn next_state(&self, state: u32, c: char) -> Option<u32> {
+        let class = self.class(c)?;
+        let next_state = self.transitions[state as usize * self.class_count + class as usize];
+        if next_state == DEAD_STATE {
+            None
+        } else {
+            Some(next_state)
+        }
+    }
+
+    /// Whether `c` leads from the start state to a state from which a rule can still match.
+    pub(crate) fn can_start_with(&self, c: char) -> bool {
+        self.class(c)
+            .is_some_and(|class| self.start_classes[class as usize])
+    }
+
+    /// Returns the rule and the length in bytes of the longest non-empty prefix of `input`
+    /// matched by a rule.
+    pub fn longest_match(&self, input: &str) -> Option<(usize, usize)> {
+        let mut state = self.start_state;
+        let mut res = None;
+        for (offset, c) in input.char_indices() {
+            state = match self.next_state(state, c) {
+                Some(state) => state,
+                None => break,
+            };
+            if let Some(rule) = self.accepting_rule(state) {
+                res = Some((rule, offset + c.len_utf8()));
+            }
+        }
+        res
+    }
+}
@@ -0,0 +1,515 @@
+//! A text format for lexer specifications, loaded into a `ModalLexerSpec<String>` whose token
+//! kinds are the token names of the rules.
+//!
+//! A specification has a definitions section and a rules section, separated by a `%%` line.
+//! Blank lines and lines starting with `#` are ignored, and so is anything after a `#` word that
+//! follows a pattern.
+//!
+//! ```text
+//! # Definitions, `NAME PATTERN`, usable as `{NAME}` in later patterns.
+//! digit   [0-9]
+//! ident   [A-Za-z_][A-Za-z0-9_]*
+//!
+//! %%
+//! # Rules, `PATTERN TOKEN_NAME [ACTION]`, the earliest of the longest matches winning.
+//! {digit}+        NUMBER
+//! "if"            IF
+//! {ident}         IDENT
+//! [ \t\n]+        skip
+//! "/*"            skip    push comment
+//!
+//! # Rules after `%mode NAME` belong to that mode, rules before the first `%mode` to `INITIAL`.
+//! %mode comment
+//! "*/"            skip    pop
+//! .|\n            skip
+//! ```
+//!
+//! Tokens named `skip` are consumed without being emitted. The actions are `push MODE`, `pop`
+//! and `switch MODE`, see `Action`. The first mode of the specification is the initial mode.
+//!
+//! Patterns end at the first whitespace outside of quotes and brackets, and support:
+//!
+//! - `"..."` literal text, and any other character not listed here matching itself;
+//! - `.` any character but `\n`, and `[...]`, `[^...]` classes of characters and ranges;
+//! - `\n`, `\t`, `\r`, `\0`, `\u{hex}` escapes, the classes `\d`, `\s`, `\w`, and `\c` for any
+//!   other character `c`;
+//! - `(...)` groups, `|` alternation, `*`, `+`, `?`, `{n}`, `{n,}`, `{n,m}` repetition with
+//!   counts up to `MAX_REPETITION`;
+//! - `{NAME}` the pattern of a definition.
+
+use crate::lexer::modes::{ModalLexerDFA, ModalLexerSpec};
+use crate::lexer::pattern::Pattern;
+use crate::lexer::spec::{Action, Rule};
+use crate::unicode::char_class::{CharClass, CharRange};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Mode of the rules before the first `%mode` line.
+pub const INITIAL_MODE: &str = "INITIAL";
+
+/// Token name of skip rules.
+pub const SKIP: &str = "skip";
+
+/// Largest count allowed in a `{n}`, `{n,}` or `{n,m}` repetition. Every repetition is compiled
+/// into that many copies of its pattern, so larger counts are rejected.
+pub const MAX_REPETITION: usize = 1000;
+
+/// An invalid line of a lexer specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpecFileError {
+    /// 1-based line number.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for SpecFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error on line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for SpecFileError {}
+
+type Result<T> = std::result::Result<T, SpecFileError>;
+
+impl ModalLexerSpec<String> {
+    /// Parses a lexer specification in the format described in the `spec_file` module.
+    /// ```
+    /// use token_scanner::lexer::ModalLexerSpec;
+    ///
+    /// let spec = ModalLexerSpec::from_spec_str("digit [0-9]\n%%\n{digit}+ NUMBER\n\" \" skip\n");
+    /// let lexer = spec.unwrap().compile().unwrap();
+    /// let kinds: Vec<String> = lexer.lex("1 23").map(|token| token.unwrap().kind).collect();
+    /// assert_eq!(kinds, vec!["NUMBER", "NUMBER"]);
+    /// ```
+    pub fn from_spec_str(text: &str) -> Result<Self> {
+        let mut spec = ModalLexerSpec::new();
+        let mut definitions = HashMap::new();
+        let mut in_rules = false;
+        let mut mode = INITIAL_MODE.to_string();
+        // Modes referred to by actions, with their lines.
+        let mut mode_references = vec![];
+        let mut line_count = 0;
+        for (idx, line) in text.lines().enumerate() {
+            let line_number = idx + 1;
+            line_count = line_number;
+            let error = |message: String| SpecFileError {
+                line: line_number,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line == "%%" {
+                if in_rules {
+                    return Err(error("unexpected second `%%`".to_string()));
+                }
+                in_rules = true;
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            if words.next() == Some("%mode") {
+                if !in_rules {
+                    return Err(error("`%mode` before `%%`".to_string()));
+                }
+                mode = match (words.next(), words.next()) {
+                    (Some(name), None) => name.to_string(),
+                    _ => return Err(error("expected `%mode NAME`".to_string())),
+                };
+                spec.mode(&mode);
+                continue;
+            }
+            if in_rules {
+                let (pattern, rest) = parse_pattern(line, &definitions).map_err(error)?;
+                let (kind, action) = parse_rule_words(rest).map_err(error)?;
+                if let Some(Action::Push(name)) | Some(Action::Switch(name)) = &action {
+                    mode_references.push((name.clone(), line_number));
+                }
+                spec.mode(&mode).add_rule(Rule {
+                    skip: kind == SKIP,
+                    kind,
+                    pattern,
+                    action,
+                });
+            } else {
+                let (name, pattern) = parse_definition(line, &definitions).map_err(error)?;
+                if definitions.contains_key(&name) {
+                    return Err(error(format!("definition `{}` is defined twice", name)));
+                }
+                definitions.insert(name, pattern);
+            }
+        }
+        if !in_rules {
+            return Err(SpecFileError {
+                line: line_count.max(1),
+                message: "missing `%%` before the rules".to_string(),
+            });
+        }
+        for (name, line) in mode_references {
+            if !spec.modes().iter().any(|(mode, _)| *mode == name) {
+                return Err(SpecFileError {
+                    line,
+                    message: format!("unknown mode `{}`", name),
+                });
+            }
+        }
+        if spec.modes().is_empty() {
+            spec.mode(INITIAL_MODE);
+        }
+        Ok(spec)
+    }
+}
+
+impl ModalLexerDFA<String> {
+    /// Parses a lexer specification with `ModalLexerSpec::from_spec_str` and compiles it.
+    pub fn from_spec_str(text: &str) -> Result<Self> {
+        // Unknown modes are reported with their lines by the parser.
+        Ok(ModalLexerSpec::from_spec_str(text)?.compile().unwrap())
+    }
+}
+
+fn parse_definition(
+    line: &str,
+    definitions: &HashMap<String, Pattern>,
+) -> std::result::Result<(String, Pattern), String> {
+    let name_len = line.find(char::is_whitespace).unwrap_or(line.len());
+    let (name, rest) = line.split_at(name_len);
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_identifier {
+        return Err(format!("invalid definition name `{}`", name));
+    }
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        return Err(format!("definition `{}` has no pattern", name));
+    }
+    let (pattern, rest) = parse_pattern(rest, definitions)?;
+    match rest.split_whitespace().next() {
+        Some(word) if !word.starts_with('#') => {
+            Err(format!("unexpected `{}` after the pattern", word))
+        }
+        _ => Ok((name.to_string(), pattern)),
+    }
+}
+
+/// Parses the token name and the action following the pattern of a rule.
+fn parse_rule_words(rest: &str) -> std::result::Result<(String, Option<Action>), String> {
+    let words: Vec<&str> = rest
+        .split_whitespace()
+        .take_while(|word| !word.starts_with('#'))
+        .collect();
+    let (kind, action) = match words.split_first() {
+        Some((kind, action)) => (kind.to_string(), action),
+        None => return Err("missing token name after the pattern".to_string()),
+    };
+    let action = match action {
+        [] => None,
+        ["pop"] => Some(Action::Pop),
+        ["push", mode] => Some(Action::Push(mode.to_string())),
+        ["switch", mode] => Some(Action::Switch(mode.to_string())),
+        _ => {
+            return Err(format!(
+                "invalid action `{}`, expected `push MODE`, `pop` or `switch MODE`",
+                action.join(" ")
+            ))
+        }
+    };
+    Ok((kind, action))
+}
+
+/// Parses the pattern at the start of `text`, returning the rest of `text` after it.
+fn parse_pattern<'a>(
+    text: &'a str,
+    definitions: &HashMap<String, Pattern>,
+) -> std::result::Result<(Pattern, &'a str), String> {
+    let mut parser = PatternParser {
+        text,
+        position: 0,
+        definitions,
+    };
+    let pattern = parser.parse_alternation()?;
+    match parser.peek() {
+        Some(')') => Err("unmatched `)`".to_string()),
+        Some(c) if !c.is_whitespace() => Err(format!("unexpected `{}`", c)),
+        _ => Ok((pattern, &text[parser.position..])),
+    }
+}
+
+/// A character or a class of characters written with a backslash.
+enum Escape {
+    Char(char),
+    Class(CharClass),
+}
+
+struct PatternParser<'a, 'b> {
+    text: &'a str,
+    /// Byte offset of the next character.
+    position: usize,
+    definitions: &'b HashMap<String, Pattern>,
+}
+
+impl<'a, 'b> PatternParser<'a, 'b> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_alternation(&mut self) -> std::result::Result<Pattern, String> {
+        let mut patterns = vec![self.parse_concat()?];
+        while self.eat('|') {
+            patterns.push(self.parse_concat()?);
+        }
+        Ok(if patterns.len() == 1 {
+            patterns.pop().unwrap()
+        } else {
+            Pattern::alternation(patterns)
+        })
+    }
+
+    fn parse_concat(&mut self) -> std::result::Result<Pattern, String> {
+        let mut patterns = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' || c.is_whitespace() {
+                break;
+            }
+            patterns.push(self.parse_repeat()?);
+        }
+        Ok(if patterns.len() == 1 {
+            patterns.pop().unwrap()
+        } else {
+            Pattern::concat(patterns)
+        })
+    }
+
+    fn parse_repeat(&mut self) -> std::result::Result<Pattern, String> {
+        let mut pattern = self.parse_atom()?;
+        loop {
+            pattern = match self.peek() {
+                Some('*') => pattern.star(),
+                Some('+') => pattern.plus(),
+                Some('?') => pattern.optional(),
+                Some('{')
+                    if self.text[self.position + 1..].starts_with(|c: char| c.is_ascii_digit()) =>
+                {
+                    self.next();
+                    let (min, max) = self.parse_counts()?;
+                    pattern = pattern.repeat(min, max);
+                    continue;
+                }
+                _ => return Ok(pattern),
+            };
+            self.next();
+        }
+    }
+
+    /// Parses the counts of a `{n}`, `{n,}` or `{n,m}` repetition after the `{`.
+    fn parse_counts(&mut self) -> std::result::Result<(usize, Option<usize>), String> {
+        let min = self.parse_number()?;
+        let max = if self.eat(',') {
+            match self.peek() {
+                Some('}') => None,
+                _ => Some(self.parse_number()?),
+            }
+        } else {
+            Some(min)
+        };
+        if !self.eat('}') {
+            return Err("unclosed repetition".to_string());
+        }
+        match max {
+            Some(max) if max < min => Err(format!(
+                "repetition maximum {} is below its minimum {}",
+                max, min
+            )),
+            _ if min.max(max.unwrap_or(min)) > MAX_REPETITION => Err(format!(
+                "repetition count {} is above the maximum {}",
+                min.max(max.unwrap_or(min)),
+                MAX_REPETITION
+            )),
+            _ => Ok((min, max)),
+        }
+    }
+
+    fn parse_number(&mut self) -> std::result::Result<usize, String> {
+        let rest = &self.text[self.position..];
+        let len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number = rest[..len]
+            .parse()
+            .map_err(|_| "invalid repetition count".to_string())?;
+        self.position += len;
+        Ok(number)
+    }
+
+    fn parse_atom(&mut self) -> std::result::Result<Pattern, String> {
+        match self.next() {
+            None => Err("unexpected end of pattern".to_string()),
+            Some('(') => {
+                let pattern = self.parse_alternation()?;
+                if !self.eat(')') {
+                    return Err("unclosed group".to_string());
+                }
+                Ok(pattern)
+            }
+            Some('[') => self.parse_class(),
+            Some('"') => self.parse_string(),
+            Some('.') => Ok(Pattern::class(
+                CharClass::any().difference(&CharClass::from('\n')),
+            )),
+            Some('{') => {
+                let rest = &self.text[self.position..];
+                let len = rest
+                    .find('}')
+                    .ok_or_else(|| "unclosed definition reference".to_string())?;
+                let name = &rest[..len];
+                self.position += len + 1;
+                self.definitions
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| format!("undefined definition `{}`", name))
+            }
+            Some('\\') => Ok(match self.parse_escape()? {
+                Escape::Char(c) => Pattern::literal(&c.to_string()),
+                Escape::Class(class) => Pattern::class(class),
+            }),
+            Some(c @ '*') | Some(c @ '+') | Some(c @ '?') => {
+                Err(format!("`{}` has nothing to repeat", c))
+            }
+            Some(c) => Ok(Pattern::literal(&c.to_string())),
+        }
+    }
+
+    /// Parses an escape after the `\`.
+    fn parse_escape(&mut self) -> std::result::Result<Escape, String> {
+        let c = match self.next() {
+            None => return Err("unfinished escape".to_string()),
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some('u') => {
+                let rest = &self.text[self.position..];
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .map(|(code, _)| code)
+                    .ok_or_else(|| "expected `\\u{hex}`".to_string())?;
+                self.position += code.len() + 2;
+                u32::from_str_radix(code, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or_else(|| format!("invalid character code `{}`", code))?
+            }
+            Some('d') => return Ok(Escape::Class(CharRange::new('0', '9').into())),
+            Some('s') => {
+                return Ok(Escape::Class(
+                    [' ', '\t', '\n', '\r', '\x0B', '\x0C']
+                        .iter()
+                        .map(|c| CharRange::single(*c))
+                        .collect(),
+                ))
+            }
+            Some('w') => {
+                return Ok(Escape::Class(
+                    vec![
+                        CharRange::new('a', 'z'),
+                        CharRange::new('A', 'Z'),
+                        CharRange::new('0', '9'),
+                        CharRange::single('_'),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ))
+            }
+            Some(c) => c,
+        };
+        Ok(Escape::Char(c))
+    }
+
+    /// Parses a class after the `[`.
+    fn parse_class(&mut self) -> std::result::Result<Pattern, String> {
+        let negated = self.eat('^');
+        let mut class = CharClass::new();
+        let mut empty = true;
+        loop {
+            let start = match self.next() {
+                None => return Err("unclosed character class".to_string()),
+                Some(']') if empty => return Err("empty character class".to_string()),
+                Some(']') => break,
+                Some('\\') => match self.parse_escape()? {
+                    Escape::Char(c) => c,
+                    Escape::Class(escaped) => {
+                        class = class.union(&escaped);
+                        empty = false;
+                        continue;
+                    }
+                },
+                Some(c) => c,
+            };
+            empty = false;
+            // A `-` before the closing `]` is a character.
+            let is_range =
+                self.peek() == Some('-') && !self.text[self.position + 1..].starts_with(']');
+            if !is_range {
+                class = class.union(&CharClass::from(start));
+                continue;
+            }
+            self.next();
+            let end = match self.next() {
+                Some('\\') => match self.parse_escape()? {
+                    Escape::Char(c) => c,
+                    Escape::Class(_) => return Err("character class escape in a range".to_string()),
+                },
+                Some(c) => c,
+                None => return Err("unclosed character class".to_string()),
+            };
+            if end < start {
+                return Err(format!("range `{}-{}` is out of order", start, end));
+            }
+            class = class.union(&CharRange::new(start, end).into());
+        }
+        Ok(Pattern::class(if negated {
+            class.negated()
+        } else {
+            class
+        }))
+    }
+
+    /// Parses a quoted string after the `"`.
+    fn parse_string(&mut self) -> std::result::Result<Pattern, String> {
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                None => return Err("unclosed string".to_string()),
+                Some('"') => return Ok(Pattern::literal(&text)),
+                Some('\\') => match self.parse_escape()? {
+                    Escape::Char(c) => text.push(c),
+                    Escape::Class(_) => {
+                        return Err("character class escape in a string".to_string())
+                    }
+                },
+                Some(c) => text.push(c),
+            }
+        }
+    }
+}
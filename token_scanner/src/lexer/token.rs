@@ -0,0 +1,50 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result};
+
+/// Byte offsets `start..end` of a lexeme in the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<'a, K> {
+    pub kind: K,
+    pub lexeme: &'a str,
+    pub span: Span,
+}
+
+/// No rule matches a non-empty prefix of the input at `position`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LexError {
+    pub position: usize,
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "no rule matches at byte {}", self.position)
+    }
+}
+
+impl Error for LexError {}
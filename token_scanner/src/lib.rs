@@ -1,8 +1,14 @@
 pub mod automaton;
 pub mod custom_traits;
 pub mod examples;
+pub mod formats;
+pub mod lexer;
+pub mod unicode;
 
+pub use automaton::alphabet_classes::AlphabetClasses;
+pub use automaton::dense_dfa::DenseDFA;
 pub use automaton::dfa::DFA;
+pub use automaton::error::InvalidAutomatonError;
 pub use automaton::nfa::NFA;
 pub use custom_traits::alphabet::{Alphabet, StateIdentifier};
 pub use custom_traits::finite_automaton::{
@@ -0,0 +1,195 @@
+use crate::unicode::char_class::{split_disjoint, CharClass, CharRange, CharSet};
+use crate::unicode::utf8::{to_utf8, Utf8State};
+use crate::{DFATransitionMap, FiniteAutomaton, NFATransitionMap, StateIdentifier, DFA, NFA};
+use std::collections::{HashMap, HashSet};
+
+/// Running automata whose alphabets are sets of characters on plain characters.
+pub trait CharAutomaton<T, U>: FiniteAutomaton<T, U>
+where
+    T: CharSet,
+    U: StateIdentifier,
+{
+    /// Like `accept`, but every character follows all transitions whose alphabet contains it, so
+    /// overlapping alphabets behave like an NFA.
+    /// ```
+    /// use maplit::{hashmap, hashset};
+    /// use token_scanner::unicode::char_automaton::CharAutomaton;
+    /// use token_scanner::unicode::char_class::CharRange;
+    /// use token_scanner::NFA;
+    ///
+    /// let nfa = NFA::from_map(
+    ///     0,
+    ///     hashset! {1},
+    ///     hashmap! {
+    ///         0 => hashmap! {Some(CharRange::new('a', 'z')) => hashset! {1}},
+    ///         1 => hashmap! {Some(CharRange::new('0', '9')) => hashset! {1}},
+    ///     },
+    /// );
+    /// assert!(nfa.accept_chars("x42".chars()));
+    /// assert!(!nfa.accept_chars("42".chars()));
+    /// ```
+    fn accept_chars<S>(&self, content: S) -> bool
+    where
+        S: Iterator<Item = char>,
+    {
+        let mut cur_states = self.epsilon_closure_states(self.start_state());
+        for c in content {
+            let alphabets: Vec<&T> = self
+                .alphabets()
+                .iter()
+                .filter(|alphabet| alphabet.contains_char(c))
+                .collect();
+            let mut next_states = HashSet::new();
+            for state in cur_states.iter() {
+                for alphabet in alphabets.iter() {
+                    next_states.extend(
+                        self.epsilon_closure_transition(state.clone(), (*alphabet).clone()),
+                    );
+                }
+            }
+            cur_states = next_states;
+        }
+        self.accepted_states()
+            .iter()
+            .any(|state| cur_states.contains(state))
+    }
+
+    /// Converts into an equivalent automaton over the bytes of the UTF-8 encoding, which accepts
+    /// the encodings of the accepted strings and rejects invalid UTF-8. Multi-byte characters go
+    /// through `Utf8State::Continuation` states, shared between transitions into the same state.
+    /// ```
+    /// use maplit::{hashmap, hashset};
+    /// use token_scanner::unicode::char_automaton::CharAutomaton;
+    /// use token_scanner::unicode::char_class::CharRange;
+    /// use token_scanner::{FiniteAutomaton, NFA};
+    ///
+    /// let nfa = NFA::from_map(
+    ///     0,
+    ///     hashset! {1},
+    ///     hashmap! {0 => hashmap! {Some(CharRange::new('α', 'ω')) => hashset! {1}}},
+    /// );
+    /// let bytes = nfa.to_utf8();
+    /// assert!(bytes.accept("λ".bytes()));
+    /// assert!(!bytes.accept(vec![0xCE].into_iter()));
+    /// ```
+    fn to_utf8(&self) -> NFA<u8, Utf8State<U>> {
+        to_utf8(self)
+    }
+}
+
+impl<T, U, F> CharAutomaton<T, U> for F
+where
+    T: CharSet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+}
+
+/// Splits the alphabets of a finite automaton into disjoint ranges, returning the ranges of each
+/// alphabet.
+fn split_alphabets<T: CharSet>(
+    alphabets: &HashSet<T>,
+) -> (HashMap<T, Vec<CharRange>>, HashSet<CharRange>) {
+    let alphabets: Vec<&T> = alphabets.iter().collect();
+    let classes: Vec<CharClass> = alphabets
+        .iter()
+        .map(|alphabet| alphabet.to_char_class())
+        .collect();
+    let pieces = split_disjoint(&classes);
+    let all_ranges = pieces.iter().flatten().cloned().collect();
+    let alphabet_to_ranges = alphabets.into_iter().cloned().zip(pieces).collect();
+    (alphabet_to_ranges, all_ranges)
+}
+
+impl<T, U> NFA<T, U>
+where
+    T: CharSet,
+    U: StateIdentifier,
+{
+    /// Returns an equivalent NFA whose alphabets are character ranges. The alphabets of an NFA are
+    /// disjoint since its construction, so this only changes their type, which `to_dfa` does not
+    /// need.
+    /// ```
+    /// use maplit::{hashmap, hashset};
+    /// use token_scanner::unicode::char_automaton::CharAutomaton;
+    /// use token_scanner::unicode::char_class::{CharClass, CharRange};
+    /// use token_scanner::{FiniteAutomaton, NFA};
+    ///
+    /// // A single lowercase letter, or the keyword "if".
+    /// let nfa = NFA::from_map(
+    ///     0,
+    ///     hashset! {1, 3},
+    ///     hashmap! {
+    ///         0 => hashmap! {
+    ///             Some(CharClass::from(CharRange::new('a', 'z'))) => hashset! {1},
+    ///             Some(CharClass::from('i')) => hashset! {2},
+    ///         },
+    ///         2 => hashmap! {Some(CharClass::from('f')) => hashset! {3}},
+    ///     },
+    /// );
+    /// // a-e, f, g-h, i and j-z.
+    /// assert_eq!(nfa.alphabets().len(), 5);
+    /// let dfa = nfa.split_ranges().to_dfa();
+    /// assert_eq!(dfa.alphabets().len(), 5);
+    /// assert!(dfa.accept_chars("i".chars()));
+    /// assert!(dfa.accept_chars("if".chars()));
+    /// assert!(!dfa.accept_chars("of".chars()));
+    /// ```
+    pub fn split_ranges(&self) -> NFA<CharRange, U> {
+        let (alphabet_to_ranges, all_ranges) = split_alphabets(self.alphabets());
+        let mut transition_map: NFATransitionMap<CharRange, U> = HashMap::new();
+        for (state, map) in self.transition_map() {
+            let new_map = transition_map.entry(state.clone()).or_default();
+            for (alphabet, to_states) in map {
+                match alphabet {
+                    None => new_map
+                        .entry(None)
+                        .or_default()
+                        .extend(to_states.iter().cloned()),
+                    Some(alphabet) => {
+                        for range in alphabet_to_ranges.get(alphabet).unwrap() {
+                            new_map
+                                .entry(Some(*range))
+                                .or_default()
+                                .extend(to_states.iter().cloned());
+                        }
+                    }
+                }
+            }
+        }
+        NFA::from_formal(
+            self.states().clone(),
+            all_ranges,
+            self.start_state(),
+            self.accepted_states().clone(),
+            transition_map,
+        )
+    }
+}
+
+impl<T, U> DFA<T, U>
+where
+    T: CharSet,
+    U: StateIdentifier,
+{
+    /// Returns an equivalent DFA whose alphabets are character ranges, like `NFA::split_ranges`.
+    pub fn split_ranges(&self) -> DFA<CharRange, U> {
+        let (alphabet_to_ranges, all_ranges) = split_alphabets(self.alphabets());
+        let mut transition_map: DFATransitionMap<CharRange, U> = HashMap::new();
+        for (state, map) in self.transition_map() {
+            let new_map = transition_map.entry(state.clone()).or_default();
+            for (alphabet, to_state) in map {
+                for range in alphabet_to_ranges.get(alphabet).unwrap() {
+                    new_map.insert(*range, to_state.clone());
+                }
+            }
+        }
+        DFA::from_formal(
+            self.states().clone(),
+            all_ranges,
+            self.start_state(),
+            self.accepted_states().clone(),
+            transition_map,
+        )
+    }
+}
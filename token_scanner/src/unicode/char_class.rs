@@ -0,0 +1,427 @@
+use crate::custom_traits::alphabet::NoneEmptyAlphabet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{Debug, Display, Formatter, Result};
+use std::iter::FromIterator;
+use std::sync::OnceLock;
+use unicode_general_category::{get_general_category, GeneralCategory};
+
+const SURROGATE_START: u32 = 0xD800;
+const SURROGATE_END: u32 = 0xDFFF;
+
+/// The character after `c`, skipping surrogates.
+pub(crate) fn next_char(c: char) -> Option<char> {
+    match c as u32 {
+        0xD7FF => Some('\u{E000}'),
+        val => std::char::from_u32(val + 1),
+    }
+}
+
+/// The character before `c`, skipping surrogates.
+pub(crate) fn prev_char(c: char) -> Option<char> {
+    match c as u32 {
+        0 => None,
+        0xE000 => Some('\u{D7FF}'),
+        val => std::char::from_u32(val - 1),
+    }
+}
+
+/// An inclusive range of characters, used as an alphabet so that `[a-z]` is a single transition.
+/// ```
+/// use token_scanner::unicode::char_class::CharRange;
+///
+/// let lower = CharRange::new('a', 'z');
+/// assert!(lower.contains('q'));
+/// assert_eq!(lower.to_string(), "a-z");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CharRange {
+    start: char,
+    end: char,
+}
+
+impl NoneEmptyAlphabet for CharRange {
+    fn split_overlapping(alphabets: &HashSet<Self>) -> Option<HashMap<Self, Vec<Self>>> {
+        split_overlapping_sets(alphabets, |range| range)
+    }
+}
+
+impl CharRange {
+    /// # Panics
+    ///
+    /// Panics if `start` is after `end`.
+    pub fn new(start: char, end: char) -> Self {
+        assert!(
+            start <= end,
+            "Invalid character range {:?}-{:?}.",
+            start,
+            end
+        );
+        Self { start, end }
+    }
+
+    pub fn single(c: char) -> Self {
+        Self::new(c, c)
+    }
+
+    pub fn start(&self) -> char {
+        self.start
+    }
+
+    pub fn end(&self) -> char {
+        self.end
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.start <= c && c <= self.end
+    }
+
+    /// Number of characters in the range, not counting surrogates.
+    pub fn len(&self) -> usize {
+        let (start, end) = (self.start as u32, self.end as u32);
+        let surrogates = if start < SURROGATE_START && end > SURROGATE_END {
+            SURROGATE_END - SURROGATE_START + 1
+        } else {
+            0
+        };
+        (end - start + 1 - surrogates) as usize
+    }
+
+    /// Always `false`, a range contains at least one character.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+impl Display for CharRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.start == self.end {
+            write!(f, "{}", escape(self.start))
+        } else {
+            write!(f, "{}-{}", escape(self.start), escape(self.end))
+        }
+    }
+}
+
+impl Debug for CharRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Escapes characters that would be ambiguous or invisible in labels, like whitespace and the
+/// separators of ranges, sets and transition tables.
+fn escape(c: char) -> String {
+    if c.is_control() || c.is_whitespace() || "\\-,[]{}\"^".contains(c) {
+        format!("\\u{{{:x}}}", c as u32)
+    } else {
+        c.to_string()
+    }
+}
+
+/// The ranges of every general category. Looking up the category of a character is slow, so all
+/// characters are looked up once, on first use.
+fn general_category_ranges() -> &'static HashMap<GeneralCategory, Vec<CharRange>> {
+    static RANGES: OnceLock<HashMap<GeneralCategory, Vec<CharRange>>> = OnceLock::new();
+    RANGES.get_or_init(|| {
+        let mut category_to_ranges: HashMap<GeneralCategory, Vec<CharRange>> = HashMap::new();
+        let mut run: Option<(GeneralCategory, CharRange)> = None;
+        for c in '\0'..=std::char::MAX {
+            let category = get_general_category(c);
+            match run.as_mut() {
+                Some((run_category, range)) if *run_category == category => range.end = c,
+                _ => {
+                    let next_run = (category, CharRange::single(c));
+                    if let Some((run_category, range)) = run.replace(next_run) {
+                        category_to_ranges
+                            .entry(run_category)
+                            .or_default()
+                            .push(range);
+                    }
+                }
+            }
+        }
+        if let Some((run_category, range)) = run {
+            category_to_ranges
+                .entry(run_category)
+                .or_default()
+                .push(range);
+        }
+        category_to_ranges
+    })
+}
+
+/// A set of characters, stored as sorted, disjoint and non-adjacent ranges. Supports union,
+/// intersection and negation, and can be built from Unicode general categories.
+/// ```
+/// use token_scanner::unicode::char_class::{CharClass, CharRange};
+/// use token_scanner::unicode::GeneralCategory;
+///
+/// let letters = CharClass::from_general_categories(&[
+///     GeneralCategory::UppercaseLetter,
+///     GeneralCategory::LowercaseLetter,
+/// ]);
+/// let non_ascii_letters = letters.intersection(&CharClass::from(CharRange::new('\0', '\x7f')).negated());
+/// assert!(non_ascii_letters.contains('é'));
+/// assert!(!non_ascii_letters.contains('e'));
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CharClass {
+    ranges: Vec<CharRange>,
+}
+
+impl NoneEmptyAlphabet for CharClass {
+    fn split_overlapping(alphabets: &HashSet<Self>) -> Option<HashMap<Self, Vec<Self>>> {
+        split_overlapping_sets(alphabets, CharClass::from)
+    }
+}
+
+impl CharClass {
+    /// The empty class.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The class of all characters.
+    pub fn any() -> Self {
+        Self::from(CharRange::new('\0', std::char::MAX))
+    }
+
+    pub fn from_general_category(category: GeneralCategory) -> Self {
+        Self::from_general_categories(&[category])
+    }
+
+    /// The characters in any of `categories`.
+    pub fn from_general_categories(categories: &[GeneralCategory]) -> Self {
+        let category_to_ranges = general_category_ranges();
+        categories
+            .iter()
+            .filter_map(|category| category_to_ranges.get(category))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    pub fn ranges(&self) -> &[CharRange] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of characters in the class.
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(CharRange::len).sum()
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        match self.ranges.binary_search_by(|range| range.start.cmp(&c)) {
+            Ok(_) => true,
+            Err(0) => false,
+            Err(idx) => self.ranges[idx - 1].contains(c),
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges
+            .iter()
+            .chain(other.ranges.iter())
+            .cloned()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start <= end {
+                ranges.push(CharRange::new(start, end));
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// All characters not in the class.
+    pub fn negated(&self) -> Self {
+        let mut ranges = vec![];
+        let mut next_start = Some('\0');
+        for range in self.ranges.iter() {
+            if let Some(start) = next_start {
+                if start < range.start {
+                    ranges.push(CharRange::new(start, prev_char(range.start).unwrap()));
+                }
+            }
+            next_start = next_char(range.end);
+        }
+        if let Some(start) = next_start {
+            ranges.push(CharRange::new(start, std::char::MAX));
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.negated())
+    }
+}
+
+impl From<CharRange> for CharClass {
+    fn from(range: CharRange) -> Self {
+        Self {
+            ranges: vec![range],
+        }
+    }
+}
+
+impl From<char> for CharClass {
+    fn from(c: char) -> Self {
+        Self::from(CharRange::single(c))
+    }
+}
+
+impl FromIterator<CharRange> for CharClass {
+    /// Sorts and merges overlapping or adjacent ranges.
+    fn from_iter<I: IntoIterator<Item = CharRange>>(iter: I) -> Self {
+        let mut sorted: Vec<CharRange> = iter.into_iter().collect();
+        sorted.sort();
+        let mut ranges: Vec<CharRange> = vec![];
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last) if next_char(last.end).is_none_or(|c| c >= range.start) => {
+                    last.end = last.end.max(range.end)
+                }
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+}
+
+impl Display for CharClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "[")?;
+        for range in self.ranges.iter() {
+            write!(f, "{}", range)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl Debug for CharClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Alphabets that stand for a set of characters.
+pub trait CharSet: NoneEmptyAlphabet {
+    fn contains_char(&self, c: char) -> bool;
+
+    fn to_char_class(&self) -> CharClass;
+}
+
+impl CharSet for char {
+    fn contains_char(&self, c: char) -> bool {
+        *self == c
+    }
+
+    fn to_char_class(&self) -> CharClass {
+        CharClass::from(*self)
+    }
+}
+
+impl CharSet for CharRange {
+    fn contains_char(&self, c: char) -> bool {
+        self.contains(c)
+    }
+
+    fn to_char_class(&self) -> CharClass {
+        CharClass::from(*self)
+    }
+}
+
+impl CharSet for CharClass {
+    fn contains_char(&self, c: char) -> bool {
+        self.contains(c)
+    }
+
+    fn to_char_class(&self) -> CharClass {
+        self.clone()
+    }
+}
+
+/// `NoneEmptyAlphabet::split_overlapping` for sets of characters, with every disjoint range
+/// converted into an alphabet by `piece`.
+fn split_overlapping_sets<T, F>(alphabets: &HashSet<T>, piece: F) -> Option<HashMap<T, Vec<T>>>
+where
+    T: CharSet,
+    F: Fn(CharRange) -> T,
+{
+    let alphabets: Vec<&T> = alphabets.iter().collect();
+    let classes: Vec<CharClass> = alphabets
+        .iter()
+        .map(|alphabet| alphabet.to_char_class())
+        .collect();
+    let pieces = split_disjoint(&classes);
+    let mut seen = HashSet::new();
+    if pieces.iter().flatten().all(|range| seen.insert(*range)) {
+        return None;
+    }
+    Some(
+        alphabets
+            .into_iter()
+            .cloned()
+            .zip(
+                pieces
+                    .into_iter()
+                    .map(|ranges| ranges.into_iter().map(&piece).collect()),
+            )
+            .collect(),
+    )
+}
+
+/// Splits possibly overlapping classes into disjoint ranges, returning the ranges covering each
+/// class. Two returned ranges are either equal or disjoint.
+/// ```
+/// use token_scanner::unicode::char_class::{split_disjoint, CharClass, CharRange};
+///
+/// let pieces = split_disjoint(&[
+///     CharClass::from(CharRange::new('a', 'z')),
+///     CharClass::from(CharRange::new('m', '~')),
+/// ]);
+/// assert_eq!(pieces[0], vec![CharRange::new('a', 'l'), CharRange::new('m', 'z')]);
+/// assert_eq!(pieces[1], vec![CharRange::new('m', 'z'), CharRange::new('{', '~')]);
+/// ```
+pub fn split_disjoint(classes: &[CharClass]) -> Vec<Vec<CharRange>> {
+    let mut boundaries = BTreeSet::new();
+    for range in classes.iter().flat_map(|class| class.ranges.iter()) {
+        boundaries.insert(range.start);
+        if let Some(c) = next_char(range.end) {
+            boundaries.insert(c);
+        }
+    }
+    classes
+        .iter()
+        .map(|class| {
+            let mut pieces = vec![];
+            for range in class.ranges.iter() {
+                let mut start = range.start;
+                for boundary in boundaries.range(range.start..=range.end) {
+                    if *boundary > start {
+                        pieces.push(CharRange::new(start, prev_char(*boundary).unwrap()));
+                        start = *boundary;
+                    }
+                }
+                pieces.push(CharRange::new(start, range.end));
+            }
+            pieces
+        })
+        .collect()
+}
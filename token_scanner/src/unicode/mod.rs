@@ -0,0 +1,6 @@
+//! Alphabets over ranges and classes of characters, and automata over them.
+pub mod char_automaton;
+pub mod char_class;
+pub mod utf8;
+
+pub use unicode_general_category::GeneralCategory;
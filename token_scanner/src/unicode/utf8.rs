@@ -0,0 +1,183 @@
+use crate::unicode::char_class::{CharRange, CharSet};
+use crate::{FiniteAutomaton, NFATransitionMap, StateIdentifier, NFA};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter, Result};
+
+/// States of a byte automaton built by `CharAutomaton::to_utf8`: the states of the original
+/// automaton, and the states between the bytes of a multi-byte character.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Utf8State<U> {
+    State(U),
+    Continuation(usize),
+}
+
+impl<U: StateIdentifier> StateIdentifier for Utf8State<U> {}
+
+impl<U: Display> Display for Utf8State<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Utf8State::State(state) => write!(f, "{}", state),
+            Utf8State::Continuation(idx) => write!(f, "utf8_{}", idx),
+        }
+    }
+}
+
+/// Byte ranges matching the UTF-8 encodings of a range of characters, one range per byte.
+type Utf8Sequence = Vec<(u8, u8)>;
+
+/// Splits `range` into sequences of byte ranges, such that the encodings of the characters in the
+/// range are exactly the byte strings matched by one of the sequences.
+pub(crate) fn utf8_sequences(range: CharRange) -> Vec<Utf8Sequence> {
+    let mut res = vec![];
+    let mut stack = vec![(range.start() as u32, range.end() as u32)];
+    'ranges: while let Some((start, end)) = stack.pop() {
+        // Surrogates have no encoding.
+        if start < 0xD800 && end > 0xDFFF {
+            stack.push((0xE000, end));
+            stack.push((start, 0xD7FF));
+            continue;
+        }
+        // Every sequence has a fixed length.
+        for max in [0x7F, 0x7FF, 0xFFFF] {
+            if start <= max && max < end {
+                stack.push((max + 1, end));
+                stack.push((start, max));
+                continue 'ranges;
+            }
+        }
+        if end <= 0x7F {
+            res.push(vec![(start as u8, end as u8)]);
+            continue;
+        }
+        // Every byte but the first covers a full range of continuation bytes, or the range only
+        // differs in its last byte.
+        for i in 1..4 {
+            let max = (1 << (6 * i)) - 1;
+            if start & !max != end & !max {
+                if start & max != 0 {
+                    stack.push(((start | max) + 1, end));
+                    stack.push((start, start | max));
+                    continue 'ranges;
+                }
+                if end & max != max {
+                    stack.push((end & !max, end));
+                    stack.push((start, (end & !max) - 1));
+                    continue 'ranges;
+                }
+            }
+        }
+        let (mut start_bytes, mut end_bytes) = ([0; 4], [0; 4]);
+        let start_bytes = std::char::from_u32(start)
+            .unwrap()
+            .encode_utf8(&mut start_bytes)
+            .as_bytes();
+        let end_bytes = std::char::from_u32(end)
+            .unwrap()
+            .encode_utf8(&mut end_bytes)
+            .as_bytes();
+        res.push(
+            start_bytes
+                .iter()
+                .cloned()
+                .zip(end_bytes.iter().cloned())
+                .collect(),
+        );
+    }
+    res
+}
+
+pub(crate) fn to_utf8<T, U, F>(fa: &F) -> NFA<u8, Utf8State<U>>
+where
+    T: CharSet,
+    U: StateIdentifier,
+    F: FiniteAutomaton<T, U> + ?Sized,
+{
+    let mut states: HashSet<Utf8State<U>> = fa
+        .states()
+        .iter()
+        .map(|state| Utf8State::State(state.clone()))
+        .collect();
+    let mut transition_map: NFATransitionMap<u8, Utf8State<U>> = HashMap::new();
+    // States reading the given byte ranges before moving to the given state, shared between all
+    // transitions with the same remaining bytes.
+    let mut continuations: HashMap<(U, Utf8Sequence), Utf8State<U>> = HashMap::new();
+    let mut sequences: HashMap<CharRange, Vec<Utf8Sequence>> = HashMap::new();
+
+    for state in fa.states() {
+        let from_state = Utf8State::State(state.clone());
+        let epsilon_states = fa.transition(state.clone(), None);
+        if !epsilon_states.is_empty() {
+            transition_map
+                .entry(from_state.clone())
+                .or_default()
+                .entry(None)
+                .or_default()
+                .extend(epsilon_states.into_iter().map(Utf8State::State));
+        }
+        for alphabet in fa.alphabets() {
+            let to_states = fa.transition(state.clone(), Some(alphabet.clone()));
+            if to_states.is_empty() {
+                continue;
+            }
+            for range in alphabet.to_char_class().ranges() {
+                let range_sequences = sequences
+                    .entry(*range)
+                    .or_insert_with(|| utf8_sequences(*range));
+                for to_state in to_states.iter() {
+                    for sequence in range_sequences.iter() {
+                        // Walks the sequence backwards from the target state.
+                        let mut next_state = Utf8State::State(to_state.clone());
+                        for idx in (1..sequence.len()).rev() {
+                            let key = (to_state.clone(), sequence[idx..].to_vec());
+                            let continuation = match continuations.get(&key) {
+                                Some(continuation) => continuation.clone(),
+                                None => {
+                                    let continuation = Utf8State::Continuation(continuations.len());
+                                    states.insert(continuation.clone());
+                                    add_byte_range(
+                                        &mut transition_map,
+                                        &continuation,
+                                        sequence[idx],
+                                        &next_state,
+                                    );
+                                    continuations.insert(key, continuation.clone());
+                                    continuation
+                                }
+                            };
+                            next_state = continuation;
+                        }
+                        add_byte_range(&mut transition_map, &from_state, sequence[0], &next_state);
+                    }
+                }
+            }
+        }
+    }
+    let alphabets = transition_map
+        .values()
+        .flat_map(|map| map.keys())
+        .flatten()
+        .cloned()
+        .collect();
+    NFA::from_formal(
+        states,
+        alphabets,
+        Utf8State::State(fa.start_state()),
+        fa.accepted_states()
+            .iter()
+            .map(|state| Utf8State::State(state.clone()))
+            .collect(),
+        transition_map,
+    )
+}
+
+fn add_byte_range<U: StateIdentifier>(
+    transition_map: &mut NFATransitionMap<u8, Utf8State<U>>,
+    from_state: &Utf8State<U>,
+    (start, end): (u8, u8),
+    to_state: &Utf8State<U>,
+) {
+    let map = transition_map.entry(from_state.clone()).or_default();
+    for byte in start..=end {
+        map.entry(Some(byte)).or_default().insert(to_state.clone());
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use token_scanner::examples::aho_corasick::AhoCorasick;
+    use token_scanner::unicode::char_automaton::CharAutomaton;
+    use token_scanner::unicode::char_class::CharClass;
+    use token_scanner::{pre_defined_fa, FiniteAutomaton};
+
+    fn ends_by_brute_force(keywords: &[&str], text: &str) -> Vec<Vec<usize>> {
+        let chars: Vec<char> = text.chars().collect();
+        (0..=chars.len())
+            .map(|end| {
+                let prefix: String = chars[..end].iter().collect();
+                (0..keywords.len())
+                    .filter(|idx| prefix.ends_with(keywords[*idx]))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let keywords = ["ab", "bab", "b", "abba", "ccc", "ab", "äb"];
+        let ac = AhoCorasick::new(&keywords);
+        let dfa = pre_defined_fa::keywords(&keywords);
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let len = rng.gen_range(0, 16);
+            let text: String = (0..len)
+                .map(|_| ['a', 'b', 'c', 'ä', 'x'][rng.gen_range(0, 5)])
+                .collect();
+            let expected = ends_by_brute_force(&keywords, &text);
+            assert_eq!(ac.ends_at(&text), expected, "{}", text);
+            assert_eq!(
+                dfa.accept_chars(text.chars()),
+                !expected.last().unwrap().is_empty(),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn states() {
+        let ac = AhoCorasick::new(&["he", "she", "his", "hers"]);
+        // The root and one state per distinct non-empty prefix.
+        assert_eq!(ac.dfa().states().len(), 10);
+        assert_eq!(ac.dfa().start_state(), 0);
+        assert!(ac.dfa().is_deterministic());
+        let state = ac.dfa().transition_map()[&0][&'s'];
+        let state = ac.dfa().transition_map()[&state][&'h'];
+        let state = ac.dfa().transition_map()[&state][&'e'];
+        assert_eq!(ac.outputs(state), &[0, 1]);
+        assert!(ac.keywords().iter().eq(["he", "she", "his", "hers"].iter()));
+    }
+
+    #[test]
+    fn other_characters_go_back_to_the_start_state() {
+        let dfa = pre_defined_fa::keywords(&["if", "in"]);
+        // `i`, `f`, `n` and every other character.
+        assert_eq!(dfa.alphabets().len(), 4);
+        assert!(dfa.accept_chars("let x = 1; if".chars()));
+        assert!(dfa.accept_chars("λ in".chars()));
+        assert!(!dfa.accept_chars("i f".chars()));
+        assert!(!dfa.accept_chars("".chars()));
+        let other = dfa
+            .alphabets()
+            .iter()
+            .find(|class| class.contains('x'))
+            .unwrap()
+            .clone();
+        let i = CharClass::from('i');
+        let f = CharClass::from('f');
+        let input = vec![i.clone(), other.clone(), i, f, other];
+        assert_eq!(dfa.longest_match(input.into_iter()), Some(4));
+    }
+
+    #[test]
+    fn empty_keyword_ends_everywhere() {
+        let ac = AhoCorasick::new(&["", "a"]);
+        assert_eq!(ac.ends_at("ba"), vec![vec![0], vec![0], vec![0, 1]]);
+        assert!(ac.dfa().accept("".chars()));
+    }
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use rand::Rng;
+    use token_scanner::formats::binary::BinaryFormatError;
+    use token_scanner::{pre_defined_fa, FiniteAutomaton, DFA, NFA};
+
+    fn contains_abb() -> DFA<char, usize> {
+        NFA::from_map(
+            0,
+            hashset! {3},
+            hashmap! {
+                0 => hashmap! {
+                    Some('a') => hashset! {0, 1},
+                    Some('b') => hashset! {0},
+                },
+                1 => hashmap! {
+                    Some('b') => hashset! {2},
+                },
+                2 => hashmap! {
+                    Some('b') => hashset! {3},
+                },
+                3 => hashmap! {
+                    Some('a') => hashset! {3},
+                    Some('b') => hashset! {3},
+                },
+            },
+        )
+        .to_dfa()
+        .relabeled()
+    }
+
+    #[test]
+    fn relabeled() {
+        let dfa = pre_defined_fa::bin_str_div_by_3().relabeled();
+        assert_eq!(dfa.start_state(), 0);
+        assert_eq!(dfa.states(), &hashset! {0, 1, 2});
+        assert_eq!(dfa.accepted_states(), &hashset! {0});
+        assert_eq!(
+            dfa.transition_map(),
+            &hashmap! {
+                0 => hashmap! {'0' => 0, '1' => 1},
+                1 => hashmap! {'0' => 2, '1' => 0},
+                2 => hashmap! {'0' => 1, '1' => 2},
+            }
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let dfa = contains_abb();
+        let bytes = dfa.to_bytes();
+        let loaded: DFA<char, usize> = DFA::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.states(), dfa.states());
+        assert_eq!(loaded.alphabets(), dfa.alphabets());
+        assert_eq!(loaded.start_state(), dfa.start_state());
+        assert_eq!(loaded.accepted_states(), dfa.accepted_states());
+        assert_eq!(loaded.transition_map(), dfa.transition_map());
+        assert_eq!(loaded.to_bytes(), bytes);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let len = rng.gen_range(0, 12);
+            let input: String = (0..len)
+                .map(|_| if rng.gen::<bool>() { 'a' } else { 'b' })
+                .collect();
+            assert_eq!(
+                loaded.accept(input.chars()),
+                input.contains("abb"),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn byte_symbols() {
+        let dfa = DFA::from_map(
+            0usize,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap! {0u8 => 1, 255u8 => 0},
+                1 => hashmap! {0u8 => 1, 255u8 => 1},
+            },
+        );
+        let loaded: DFA<u8, usize> = DFA::from_bytes(&dfa.to_bytes()).unwrap();
+        assert!(loaded.accept(vec![255, 255, 0].into_iter()));
+        assert!(!loaded.accept(vec![255].into_iter()));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let bytes = contains_abb().to_bytes();
+
+        assert!(matches!(
+            DFA::<char, usize>::from_bytes(&bytes[..10]),
+            Err(BinaryFormatError::UnexpectedEnd { .. })
+        ));
+        assert!(matches!(
+            DFA::<char, usize>::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(BinaryFormatError::UnexpectedEnd { .. })
+        ));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            DFA::<char, usize>::from_bytes(&trailing),
+            Err(BinaryFormatError::TrailingBytes { .. })
+        ));
+
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        assert_eq!(
+            DFA::<char, usize>::from_bytes(&magic).err(),
+            Some(BinaryFormatError::InvalidMagic)
+        );
+
+        let mut version = bytes.clone();
+        version[4] = 99;
+        assert_eq!(
+            DFA::<char, usize>::from_bytes(&version).err(),
+            Some(BinaryFormatError::UnsupportedVersion(99))
+        );
+
+        assert_eq!(
+            DFA::<u8, usize>::from_bytes(&bytes).err(),
+            Some(BinaryFormatError::SymbolKindMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let mut corrupt = bytes.clone();
+        let idx = bytes.len() - 8;
+        corrupt[idx] ^= 0xff;
+        assert!(matches!(
+            DFA::<char, usize>::from_bytes(&corrupt),
+            Err(BinaryFormatError::ChecksumMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use rand::Rng;
+    use token_scanner::automaton::bitset_nfa::BitsetNFA;
+    use token_scanner::{FiniteAutomaton, NFA};
+
+    #[test]
+    fn matches_generic_simulation() {
+        // More than 64 states, so active sets span several words.
+        let n = 70;
+        let mut transition_map = hashmap! {
+            0 => hashmap! {
+                Some('a') => hashset! {0, 1},
+                Some('b') => hashset! {0},
+                None => hashset! {n + 2},
+            },
+            n + 2 => hashmap! {Some('c') => hashset! {n + 3}},
+            n + 3 => hashmap! {Some('c') => hashset! {n + 3}},
+        };
+        for state in 1..=n {
+            transition_map.insert(
+                state,
+                hashmap! {Some('a') => hashset! {state + 1}, Some('b') => hashset! {state + 1}},
+            );
+        }
+        let nfa = NFA::from_map(0, hashset! {n + 1, n + 3}, transition_map);
+        let runner = BitsetNFA::new(&nfa);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let len = rng.gen_range(0, 90);
+            let input: String = (0..len)
+                .map(|_| ['a', 'b', 'b', 'c'][rng.gen_range(0, 4)])
+                .collect();
+            assert_eq!(
+                runner.accept(input.chars()),
+                nfa.accept(input.chars()),
+                "{}",
+                input
+            );
+        }
+        assert!(!runner.accept("".chars()));
+        assert!(runner.accept("ccc".chars()));
+        assert!(runner.accept(format!("a{}", "b".repeat(n)).chars()));
+        assert!(!runner.accept(format!("a{}", "b".repeat(n + 1)).chars()));
+        assert!(!runner.accept("bx".chars()));
+    }
+
+    #[test]
+    fn epsilon_cycles() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {3},
+            hashmap! {
+                0 => hashmap! {None => hashset! {1}},
+                1 => hashmap! {None => hashset! {2}, Some('x') => hashset! {3}},
+                2 => hashmap! {None => hashset! {0}, Some('y') => hashset! {1}},
+                3 => hashmap! {None => hashset! {0}},
+            },
+        );
+        let runner = BitsetNFA::new(&nfa);
+        for input in ["", "x", "yx", "xyyx", "xy", "y"] {
+            assert_eq!(
+                runner.accept(input.chars()),
+                nfa.accept(input.chars()),
+                "{}",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use rand::Rng;
+    use token_scanner::unicode::char_automaton::CharAutomaton;
+    use token_scanner::unicode::char_class::{split_disjoint, CharClass, CharRange};
+    use token_scanner::unicode::GeneralCategory;
+    use token_scanner::{FiniteAutomaton, InvalidAutomatonError, DFA, NFA};
+
+    fn class(ranges: &[(char, char)]) -> CharClass {
+        ranges
+            .iter()
+            .map(|(start, end)| CharRange::new(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn set_operations() {
+        let lower = class(&[('a', 'z')]);
+        let hex = class(&[('0', '9'), ('a', 'f'), ('A', 'F')]);
+        assert_eq!(class(&[('a', 'm'), ('n', 'z')]), lower);
+        assert_eq!(class(&[('d', 'z'), ('a', 'f')]), lower);
+        assert_eq!(
+            lower.union(&hex),
+            class(&[('0', '9'), ('A', 'F'), ('a', 'z')])
+        );
+        assert_eq!(lower.intersection(&hex), class(&[('a', 'f')]));
+        assert_eq!(hex.difference(&lower), class(&[('0', '9'), ('A', 'F')]));
+        assert_eq!(hex.len(), 22);
+        assert!(hex.contains('B') && !hex.contains('g'));
+
+        assert_eq!(lower.negated().negated(), lower);
+        assert!(CharClass::any().negated().is_empty());
+        assert_eq!(CharClass::new().negated(), CharClass::any());
+        let not_lower = lower.negated();
+        assert!(not_lower.contains('\0') && not_lower.contains('{') && !not_lower.contains('q'));
+        assert_eq!(CharClass::any().len(), 0x10FFFF + 1 - 0x800);
+
+        // Characters on both sides of the surrogates are adjacent.
+        let around_surrogates = class(&[('\u{D000}', '\u{D7FF}'), ('\u{E000}', '\u{E0FF}')]);
+        assert_eq!(around_surrogates.ranges().len(), 1);
+        assert_eq!(around_surrogates.len(), 0x800 + 0x100);
+
+        assert_eq!(hex.to_string(), "[0-9A-Fa-f]");
+        assert_eq!(
+            class(&[('-', '-'), (' ', ' ')]).to_string(),
+            "[\\u{20}\\u{2d}]"
+        );
+    }
+
+    #[test]
+    fn general_categories() {
+        let upper = CharClass::from_general_category(GeneralCategory::UppercaseLetter);
+        assert!(upper.contains('A') && upper.contains('Ä') && upper.contains('Ω'));
+        assert!(!upper.contains('a') && !upper.contains('1'));
+        let digits = CharClass::from_general_category(GeneralCategory::DecimalNumber);
+        assert!(digits.contains('7') && digits.contains('٣'));
+        assert!(upper.intersection(&digits).is_empty());
+    }
+
+    #[test]
+    fn disjoint_pieces() {
+        let classes = vec![
+            class(&[('a', 'z')]),
+            class(&[('x', 'x'), ('0', '9')]),
+            class(&[('a', 'z')]),
+        ];
+        let pieces = split_disjoint(&classes);
+        assert_eq!(
+            pieces[0],
+            vec![
+                CharRange::new('a', 'w'),
+                CharRange::single('x'),
+                CharRange::new('y', 'z')
+            ]
+        );
+        assert_eq!(
+            pieces[1],
+            vec![CharRange::new('0', '9'), CharRange::single('x')]
+        );
+        assert_eq!(pieces[2], pieces[0]);
+    }
+
+    #[test]
+    fn subset_construction_on_ranges() {
+        // Identifiers, the keyword "for", or numbers, with overlapping transitions.
+        let letter = class(&[('a', 'z'), ('A', 'Z'), ('_', '_')]);
+        let digit = class(&[('0', '9')]);
+        let nfa = NFA::from_map(
+            0,
+            hashset! {1, 4, 5},
+            hashmap! {
+                0 => hashmap! {
+                    Some(letter.clone()) => hashset! {1},
+                    Some(CharClass::from('f')) => hashset! {2},
+                    Some(digit.clone()) => hashset! {5},
+                },
+                1 => hashmap! {
+                    Some(letter.union(&digit)) => hashset! {1},
+                },
+                2 => hashmap! {Some(CharClass::from('o')) => hashset! {3}},
+                3 => hashmap! {Some(CharClass::from('r')) => hashset! {4}},
+                5 => hashmap! {Some(digit.clone()) => hashset! {5}},
+            },
+        );
+        let split = nfa.split_ranges();
+        let dfa = split.to_dfa().minimized();
+        for alphabet in split.alphabets() {
+            for other in split.alphabets() {
+                assert!(
+                    alphabet == other
+                        || alphabet.end() < other.start()
+                        || other.end() < alphabet.start()
+                );
+            }
+        }
+
+        let chars = ['a', 'f', 'o', 'r', 'Z', '_', '0', '9', '-', 'é'];
+        let mut rng = rand::thread_rng();
+        for _ in 0..300 {
+            let len = rng.gen_range(0, 6);
+            let input: String = (0..len)
+                .map(|_| chars[rng.gen_range(0, chars.len())])
+                .collect();
+            let expected = nfa.accept_chars(input.chars());
+            assert_eq!(split.accept_chars(input.chars()), expected, "{}", input);
+            assert_eq!(dfa.accept_chars(input.chars()), expected, "{}", input);
+        }
+        assert!(dfa.accept_chars("for".chars()));
+        assert!(dfa.accept_chars("x_1".chars()));
+        assert!(!dfa.accept_chars("1x".chars()));
+    }
+
+    #[test]
+    fn subset_construction_on_overlapping_ranges() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {1, 2},
+            hashmap! {
+                0 => hashmap! {
+                    Some(CharRange::new('a', 'z')) => hashset! {1},
+                    Some(CharRange::new('m', 'p')) => hashset! {2},
+                },
+                2 => hashmap! {Some(CharRange::new('m', 'p')) => hashset! {2}},
+            },
+        );
+        // a-l, m-p and q-z.
+        assert_eq!(nfa.alphabets().len(), 3);
+        let dfa = nfa.to_dfa();
+        for alphabet in dfa.alphabets() {
+            for other in dfa.alphabets() {
+                assert!(
+                    alphabet == other
+                        || alphabet.end() < other.start()
+                        || other.end() < alphabet.start()
+                );
+            }
+        }
+        for input in &["a", "m", "p", "z", "mm", "mp", "am", "ma", "zz", ""] {
+            assert_eq!(
+                dfa.accept_chars(input.chars()),
+                nfa.accept_chars(input.chars()),
+                "{}",
+                input
+            );
+        }
+        assert!(dfa.accept_chars("n".chars()));
+        assert!(dfa.accept_chars("nop".chars()));
+        assert!(!dfa.accept_chars("na".chars()));
+    }
+
+    #[test]
+    fn split_dfa() {
+        let alphabets = hashset! {class(&[('a', 'z')]), class(&[('x', 'z'), ('0', '9')])};
+        let dfa = DFA::try_from_formal(
+            hashset! {0, 1},
+            alphabets,
+            0,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap! {class(&[('a', 'z')]) => 1, class(&[('x', 'z'), ('0', '9')]) => 1},
+                1 => hashmap! {class(&[('a', 'z')]) => 1, class(&[('x', 'z'), ('0', '9')]) => 0},
+            },
+        );
+        assert_eq!(
+            dfa.err(),
+            Some(InvalidAutomatonError::OverlappingAlphabets {
+                alphabets: "[x-z]".to_string(),
+                state: "1".to_string(),
+            })
+        );
+
+        let dfa = DFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap! {class(&[('a', 'z')]) => 1, class(&[('x', 'z'), ('0', '9')]) => 1},
+                1 => hashmap! {class(&[('a', 'z')]) => 1, class(&[('x', 'z'), ('0', '9')]) => 1},
+            },
+        );
+        assert_eq!(dfa.alphabets().len(), 3);
+        let split = dfa.split_ranges();
+        assert_eq!(split.alphabets().len(), 3);
+        assert!(split.accept_chars("a9y".chars()));
+        assert!(!split.accept_chars("".chars()));
+    }
+}
@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use rand::Rng;
+    use std::collections::HashMap;
+    use token_scanner::automaton::dense_dfa::DenseDFARef;
+    use token_scanner::formats::binary::BinaryFormatError;
+    use token_scanner::{pre_defined_fa, DenseDFA, FiniteAutomaton, DFA, NFA};
+
+    fn ends_with_ab() -> DFA<char, usize> {
+        NFA::from_map(
+            0,
+            hashset! {2},
+            hashmap! {
+                0 => hashmap! {
+                    Some('a') => hashset! {0, 1},
+                    Some('b') => hashset! {0},
+                    Some('c') => hashset! {0},
+                },
+                1 => hashmap! {
+                    Some('b') => hashset! {2},
+                },
+            },
+        )
+        .to_dfa()
+        .relabeled()
+    }
+
+    fn fnv1a(bytes: &[u8]) -> u32 {
+        bytes.iter().fold(0x811c_9dc5, |hash, byte| {
+            (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193)
+        })
+    }
+
+    #[test]
+    fn matches_dfa() {
+        let dfa = ends_with_ab();
+        let dense = DenseDFA::from_dfa(&dfa);
+        assert_eq!(dense.state_count(), dfa.states().len());
+        assert_eq!(dense.alphabet_len(), 3);
+        assert_eq!(dense.class_count(), 3);
+        assert_eq!(dense.start_state(), 0);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let len = rng.gen_range(0, 10);
+            let input: Vec<char> = (0..len)
+                .map(|_| ['a', 'b', 'c'][rng.gen_range(0, 3)])
+                .collect();
+            assert_eq!(
+                dense.accept(input.iter().cloned()),
+                dfa.accept(input.iter().cloned()),
+                "{:?}",
+                input
+            );
+        }
+        assert!(!dense.accept("abx".chars()));
+        assert_eq!(dense.next_state(0, &'x'), None);
+        assert_eq!(dense.to_dfa().transition_map(), dfa.transition_map());
+    }
+
+    #[test]
+    fn alphabet_classes() {
+        // Identifiers: a letter followed by letters and digits.
+        let letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        let digits: Vec<char> = ('0'..='9').collect();
+        let mut start = HashMap::new();
+        let mut ident = HashMap::new();
+        let mut dead = HashMap::new();
+        for c in letters.iter() {
+            start.insert(*c, 1);
+            ident.insert(*c, 1);
+            dead.insert(*c, 2);
+        }
+        for c in digits.iter() {
+            start.insert(*c, 2);
+            ident.insert(*c, 1);
+            dead.insert(*c, 2);
+        }
+        let dfa = DFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {0 => start, 1 => ident, 2 => dead},
+        );
+
+        let classes = dfa.alphabet_classes();
+        assert_eq!(classes.len(), 2);
+        assert_eq!(classes.classes()[0], digits);
+        assert_eq!(classes.class(&'x'), classes.class(&'Q'));
+        assert_eq!(classes.class(&'-'), None);
+
+        let nfa_classes = NFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap! {
+                    Some('a') => hashset! {0, 1},
+                    Some('b') => hashset! {1, 0},
+                    Some('c') => hashset! {1},
+                },
+            },
+        )
+        .alphabet_classes();
+        assert_eq!(nfa_classes.classes(), &[vec!['a', 'b'], vec!['c']]);
+
+        let dense = DenseDFA::from_dfa(&dfa);
+        assert_eq!(dense.alphabet_len(), 62);
+        assert_eq!(dense.class_count(), 2);
+        assert!(dense.accept("x9Y".chars()));
+        assert!(!dense.accept("9x".chars()));
+        assert_eq!(dense.to_dfa().states().len(), 3);
+        assert!(DFA::<char, usize>::from_bytes(&dense.to_bytes())
+            .unwrap()
+            .accept("abc123".chars()));
+    }
+
+    #[test]
+    fn large_input() {
+        let dense = DenseDFA::from_dfa(&pre_defined_fa::bin_str_div_by_3());
+        let input = "110".repeat(1 << 20);
+        assert!(dense.accept(input.chars()));
+        assert!(!dense.accept(input.chars().chain(Some('1'))));
+    }
+
+    #[test]
+    fn load_in_place() {
+        let dfa = ends_with_ab();
+        let bytes = dfa.to_bytes();
+        assert_eq!(DenseDFA::from_dfa(&dfa).to_bytes(), bytes);
+
+        let loaded = DenseDFARef::<char>::from_bytes(&bytes).unwrap();
+        assert!(loaded.accept("cab".chars()));
+        assert!(!loaded.accept("aba".chars()));
+        assert_eq!(loaded.to_bytes(), bytes);
+        assert_eq!(loaded.to_dfa().transition_map(), dfa.transition_map());
+    }
+
+    #[test]
+    fn rejects_invalid_tables() {
+        let mut bytes = ends_with_ab().to_bytes();
+        // The first transition, after the 24 byte header, the 3 symbols and their classes.
+        bytes[48..52].copy_from_slice(&100u32.to_le_bytes());
+        let checksum_offset = bytes.len() - 4;
+        let checksum = fnv1a(&bytes[..checksum_offset]);
+        bytes[checksum_offset..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(
+            DenseDFARef::<char>::from_bytes(&bytes).err(),
+            Some(BinaryFormatError::InvalidState(100))
+        );
+
+        let mut bytes = ends_with_ab().to_bytes();
+        bytes[24..28].copy_from_slice(&0xd800u32.to_le_bytes());
+        let checksum = fnv1a(&bytes[..checksum_offset]);
+        bytes[checksum_offset..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(
+            DenseDFARef::<char>::from_bytes(&bytes).err(),
+            Some(BinaryFormatError::InvalidSymbol(0xd800))
+        );
+
+        let mut bytes = ends_with_ab().to_bytes();
+        bytes[36..40].copy_from_slice(&3u32.to_le_bytes());
+        let checksum = fnv1a(&bytes[..checksum_offset]);
+        bytes[checksum_offset..].copy_from_slice(&checksum.to_le_bytes());
+        assert_eq!(
+            DenseDFARef::<char>::from_bytes(&bytes).err(),
+            Some(BinaryFormatError::InvalidClass(3))
+        );
+    }
+}
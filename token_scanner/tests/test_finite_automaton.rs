@@ -57,6 +57,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn nfa_undeclared_transition_states() {
+        // Only the importers reject transitions from or to states that are not declared.
+        let nfa = NFA::from_formal(
+            hashset! {0, 1},
+            hashset! {'0'},
+            0,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap! {Some('0') => hashset! {1, 2}},
+                3 => hashmap! {Some('0') => hashset! {0}},
+            },
+        );
+        assert!(nfa.accept("0".chars()));
+    }
+
     #[test]
     fn nfa_1() {
         let nfa = NFA::from_map(
@@ -86,8 +102,8 @@ mod tests {
         assert!(dfa_min.is_deterministic());
         assert!(dfa_min.states().len() <= dfa.states().len());
 
-        let accept_strings = vec!["", "00", "01", "010", "0100", "01011"];
-        let reject_strings = vec!["0", "001", "0101"];
+        let accept_strings = ["", "00", "01", "010", "0100", "01011"];
+        let reject_strings = ["0", "001", "0101"];
 
         for string in accept_strings.iter() {
             assert!(nfa.accept(string.to_string().chars()));
@@ -158,6 +174,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn prefix_matches() {
+        // "a" followed by any number of "b", or "ab" followed by "c".
+        let nfa = NFA::from_map(
+            0,
+            hashset! {1, 3},
+            hashmap! {
+                0 => hashmap! {Some('a') => hashset! {1}, None => hashset! {4}},
+                1 => hashmap! {Some('b') => hashset! {1}},
+                4 => hashmap! {Some('a') => hashset! {5}},
+                5 => hashmap! {Some('b') => hashset! {2}},
+                2 => hashmap! {Some('c') => hashset! {3}, Some('d') => hashset! {6}},
+            },
+        );
+        assert_eq!(nfa.all_match_ends("abbc".chars()), vec![1, 2, 3]);
+        assert_eq!(nfa.all_match_ends("abc".chars()), vec![1, 2, 3]);
+        assert_eq!(nfa.longest_match("abcb".chars()), Some(3));
+        assert_eq!(nfa.longest_match("ba".chars()), None);
+        assert_eq!(nfa.longest_match("".chars()), None);
+
+        // Reading stops once only the dead state 6 is left.
+        let mut content = "abdab".chars();
+        assert_eq!(nfa.longest_match(content.by_ref()), Some(2));
+        assert_eq!(content.as_str(), "ab");
+
+        let dfa = nfa.to_dfa();
+        for length in 0..20usize {
+            let string: String = gen_random_binary_string(length)
+                .chars()
+                .map(|c| if c == '0' { 'a' } else { 'b' })
+                .collect();
+            assert_eq!(
+                dfa.all_match_ends(string.chars()),
+                nfa.all_match_ends(string.chars())
+            );
+            let expected = (0..=string.len())
+                .filter(|len| nfa.accept(string[..*len].chars()))
+                .collect::<Vec<usize>>();
+            assert_eq!(nfa.all_match_ends(string.chars()), expected);
+        }
+    }
+
     fn gen_random_binary_string(length: usize) -> String {
         let mut rng = thread_rng();
         let mut res = String::with_capacity(length);
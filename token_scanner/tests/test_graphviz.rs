@@ -0,0 +1,343 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use std::collections::HashSet;
+    use std::env;
+    use std::fs;
+    use token_scanner::formats::dot_import::GraphvizParseError;
+    use token_scanner::formats::graphviz::{GraphvizOptions, RankDir};
+    use token_scanner::unicode::char_class::CharRange;
+    use token_scanner::{pre_defined_fa, FiniteAutomaton, InvalidAutomatonError, DFA, NFA};
+
+    #[test]
+    fn nfa_1_run_frames() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {0, 2, 4},
+            hashmap! {
+                0 => hashmap!{
+                    Some('0')=> hashset!{1}
+                },
+                1 => hashmap!{
+                    Some('1')=> hashset!{2},
+                    None => hashset!{3}
+                },
+                2 => hashmap!{
+                    Some('0')=> hashset!{2},
+                    Some('1')=> hashset!{1}
+                },
+                3 => hashmap!{
+                    Some('0') => hashset!{4},
+                }
+            },
+        );
+        let mut output_dir = env::temp_dir();
+        output_dir.push("token_scanner_nfa_1_run_frames");
+        let _ = fs::remove_dir_all(&output_dir);
+        nfa.export_graphviz_run_frames("010".chars(), output_dir.to_str().unwrap().to_string())
+            .unwrap();
+
+        let mut frames: Vec<String> = fs::read_dir(&output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        frames.sort();
+        assert_eq!(
+            frames,
+            [
+                "frame_000.dot",
+                "frame_001.dot",
+                "frame_002.dot",
+                "frame_003.dot"
+            ]
+        );
+
+        // After reading "0", states 1 and 3 are active and the epsilon edge 1 -> 3 was taken.
+        let frame = fs::read_to_string(output_dir.join("frame_001.dot")).unwrap();
+        let filled_nodes = frame
+            .lines()
+            .filter(|line| line.contains("lightblue"))
+            .count();
+        assert_eq!(filled_nodes, 2);
+        assert!(frame
+            .lines()
+            .any(|line| line.contains("label=\"&#949;\"") && line.contains("red")));
+        assert_eq!(
+            frame
+                .lines()
+                .filter(|line| line.contains("color=\"red\""))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn accepted_states_start_arrow_and_merged_edges() {
+        let dfa = DFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap!{'a' => 1, 'b' => 1, 'c' => 1, '0' => 1, '1' => 1, '_' => 0},
+                1 => hashmap!{'a' => 1, 'b' => 1, 'c' => 1, '0' => 1, '1' => 1, '_' => 1},
+            },
+        );
+        let options = GraphvizOptions {
+            graph_name: "identifier".to_string(),
+            rankdir: RankDir::TopToBottom,
+            ..GraphvizOptions::default()
+        };
+        let dot = dfa.to_dot_string_with_options(&options);
+        let expected = r#"digraph "identifier" {
+    rankdir=TB;
+    node [shape="circle"];
+    __start [shape=point, style=invis];
+    STATE0 [label="0"];
+    STATE1 [label="1", shape="doublecircle"];
+    __start -> STATE0;
+    STATE0 -> STATE0 [label="_"];
+    STATE0 -> STATE1 [label="0,1,a-c"];
+    STATE1 -> STATE1 [label="0,1,_,a-c"];
+}
+"#;
+        assert_eq!(dot, expected);
+    }
+
+    #[test]
+    fn option_values_are_quoted() {
+        let options = GraphvizOptions {
+            node_shape: "box\" color=\"red".to_string(),
+            accepted_node_shape: "double circle".to_string(),
+            font_name: Some("Fira \"Code\"".to_string()),
+            ..GraphvizOptions::default()
+        };
+        let dot = pre_defined_fa::bin_str_div_by_3().to_dot_string_with_options(&options);
+        assert!(dot.contains(r#"node [shape="box\" color=\"red", fontname="Fira \"Code\""];"#));
+        assert!(dot.contains(r#"shape="double circle"]"#));
+    }
+
+    #[test]
+    fn deterministic_output() {
+        let expected = pre_defined_fa::bin_str_div_by_3().to_dot_string();
+        for _ in 0..5 {
+            let dfa = pre_defined_fa::bin_str_div_by_3();
+            assert_eq!(dfa.to_dot_string(), expected);
+        }
+    }
+
+    #[test]
+    fn export_file_matches_dot_string() {
+        let dfa = pre_defined_fa::bin_str_div_by_3();
+        let mut output_file = env::temp_dir();
+        output_file.push("token_scanner_export_file.dot");
+        dfa.export_graphviz_dot_file(output_file.to_str().unwrap().to_string())
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&output_file).unwrap(),
+            dfa.to_dot_string()
+        );
+
+        let mut output = Vec::new();
+        dfa.write_dot(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), dfa.to_dot_string());
+    }
+
+    #[test]
+    fn export_file_reports_io_error() {
+        let dfa = pre_defined_fa::bin_str_div_by_3();
+        let mut output_file = env::temp_dir();
+        output_file.push("token_scanner_missing_dir");
+        output_file.push("nested");
+        output_file.push("dfa.dot");
+        assert!(dfa
+            .export_graphviz_dot_file(output_file.to_str().unwrap().to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn import_exported_nfa() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {0, 2, 4},
+            hashmap! {
+                0 => hashmap!{
+                    Some('0')=> hashset!{1}
+                },
+                1 => hashmap!{
+                    Some('1')=> hashset!{2},
+                    None => hashset!{3}
+                },
+                2 => hashmap!{
+                    Some('0')=> hashset!{2},
+                    Some('1')=> hashset!{1}
+                },
+                3 => hashmap!{
+                    Some('0') => hashset!{4},
+                }
+            },
+        );
+        let imported = NFA::from_dot_str(&nfa.to_dot_string()).unwrap();
+        assert_eq!(imported.states().len(), nfa.states().len());
+        assert_eq!(imported.start_state(), "0");
+        assert_eq!(
+            imported.accepted_states(),
+            &hashset! {"0".to_string(), "2".to_string(), "4".to_string()}
+        );
+        for string in ["", "0", "00", "01", "001", "010", "0100", "0101", "01011"].iter() {
+            assert_eq!(
+                imported.accept(string.chars().map(|c| c.to_string())),
+                nfa.accept(string.chars()),
+                "Imported NFA disagrees on \"{}\"",
+                string
+            );
+        }
+
+        let dfa = nfa.to_dfa().minimized();
+        let imported = DFA::from_dot_str(&dfa.to_dot_string()).unwrap();
+        assert_eq!(imported.states().len(), dfa.states().len());
+        for string in ["", "0", "00", "01", "001", "010", "0100", "0101", "01011"].iter() {
+            assert_eq!(
+                imported.accept(string.chars().map(|c| c.to_string())),
+                dfa.accept(string.chars())
+            );
+        }
+    }
+
+    #[test]
+    fn import_exported_separators_and_whitespace() {
+        // `,`, `-`, `.` and `a` are merged into one edge label, as are ` ` and `\t`.
+        let dfa = DFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap!{',' => 1, '-' => 1, '.' => 1, 'a' => 1, '\\' => 0, ' ' => 0, '\t' => 0},
+                1 => hashmap!{',' => 0, '-' => 0, '.' => 0, 'a' => 0, '\\' => 1, ' ' => 1, '\t' => 1},
+            },
+        );
+        let imported = DFA::from_dot_str(&dfa.to_dot_string()).unwrap();
+        let alphabets: HashSet<String> = dfa.alphabets().iter().map(char::to_string).collect();
+        assert_eq!(imported.alphabets(), &alphabets);
+        for string in [",", "-", ".a", " ", "\t,", "\\ -", "a\t\\", ", -."].iter() {
+            assert_eq!(
+                imported.accept(string.chars().map(|c| c.to_string())),
+                dfa.accept(string.chars()),
+                "Imported DFA disagrees on {:?}",
+                string
+            );
+        }
+    }
+
+    #[test]
+    fn import_exported_ranges() {
+        let dfa = DFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap!{CharRange::new('a', 'c') => 1, CharRange::new('d', 'd') => 0, CharRange::new(' ', '-') => 1},
+                1 => hashmap!{CharRange::new('a', 'c') => 1, CharRange::new('d', 'd') => 1, CharRange::new(' ', '-') => 0},
+            },
+        );
+        let imported = DFA::from_dot_str(&dfa.to_dot_string()).unwrap();
+        let alphabets: HashSet<String> = dfa
+            .alphabets()
+            .iter()
+            .map(|range| range.to_string())
+            .collect();
+        assert_eq!(imported.alphabets(), &alphabets);
+        assert!(imported.alphabets().contains("a-c"));
+        let a_c = CharRange::new('a', 'c').to_string();
+        let d = "d".to_string();
+        assert!(imported.accept(vec![a_c.clone(), d.clone()].into_iter()));
+        let space_to_dash = CharRange::new(' ', '-').to_string();
+        assert!(!imported.accept(vec![d, a_c, space_to_dash].into_iter()));
+    }
+
+    #[test]
+    fn import_escaped_labels() {
+        let dot = r#"digraph {
+            s [style=invis];
+            s -> q0;
+            q1 [shape=doublecircle];
+            q0 -> q1 [label="\\,, \\-, \\\\, \\u{20}, a\\-c"];
+        }"#;
+        let nfa = NFA::from_dot_str(dot).unwrap();
+        assert_eq!(
+            nfa.alphabets(),
+            &hashset! {
+                ",".to_string(),
+                "-".to_string(),
+                "\\".to_string(),
+                " ".to_string(),
+                "a-c".to_string()
+            }
+        );
+
+        let invalid = "digraph { s [style=invis]; s -> a; a -> a [label=\"a,\\\\u{zz}\"]; }";
+        assert_eq!(
+            NFA::from_dot_str(invalid).err(),
+            Some(GraphvizParseError::Syntax {
+                line: 1,
+                message: "invalid escape in edge label \"a,\\u{zz}\"".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn import_hand_written_dot() {
+        let dot = r#"
+            // Strings over {a, b} ending with "ab".
+            digraph ends_with_ab {
+                node [shape = circle]
+                start [shape=none, label=""]
+                q2 [shape=doublecircle]
+                start -> q0
+                q0 -> q0 [label="a, b"]
+                q0 -> q1 [label="a"]
+                q1 -> q2 [label=b]
+                q2 -> q0 [label="ε"]
+            }
+        "#;
+        let nfa = NFA::from_dot_str(dot).unwrap();
+        assert_eq!(
+            nfa.alphabets(),
+            &hashset! {"a".to_string(), "b".to_string()}
+        );
+        let to_input = |s: &str| s.chars().map(|c| c.to_string()).collect::<Vec<_>>();
+        assert!(nfa.accept(to_input("ab").into_iter()));
+        assert!(nfa.accept(to_input("babab").into_iter()));
+        assert!(!nfa.accept(to_input("aba").into_iter()));
+        assert!(!nfa.accept(to_input("").into_iter()));
+
+        match DFA::from_dot_str(dot) {
+            Err(GraphvizParseError::InvalidGraph(_)) => {}
+            _ => panic!("An NFA with epsilon transitions is not a DFA."),
+        }
+    }
+
+    #[test]
+    fn import_invalid_dot() {
+        let missing_start = "digraph { a [shape=doublecircle]; a -> a [label=0]; }";
+        match NFA::from_dot_str(missing_start) {
+            Err(GraphvizParseError::InvalidGraph(_)) => {}
+            res => panic!("Expected invalid graph error, got {:?}", res.err()),
+        }
+
+        let unterminated = "digraph {\n    a -> b [label=\"0];\n}";
+        assert_eq!(
+            NFA::from_dot_str(unterminated).err(),
+            Some(GraphvizParseError::Syntax {
+                line: 2,
+                message: "unterminated string".to_string()
+            })
+        );
+
+        let incomplete_dfa =
+            "digraph { s [style=invis]; s -> a; a -> b [label=\"0,1\"]; b -> a [label=0]; }";
+        assert!(NFA::from_dot_str(incomplete_dfa).is_ok());
+        match DFA::from_dot_str(incomplete_dfa) {
+            Err(GraphvizParseError::InvalidAutomaton(
+                InvalidAutomatonError::MissingAlphabetTransitions { state, .. },
+            )) => assert_eq!(state, "b"),
+            res => panic!("Expected missing transition error, got {:?}", res.err()),
+        }
+    }
+}
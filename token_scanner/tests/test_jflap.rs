@@ -0,0 +1,231 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use token_scanner::formats::jflap::{to_jflap_string, JflapError};
+    use token_scanner::{pre_defined_fa, FiniteAutomaton, InvalidAutomatonError, DFA, NFA};
+
+    // Strings over {a, b} that contain "ab", as saved by JFLAP 7.1.
+    const CONTAINS_AB: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><!--Created with JFLAP 7.1.--><structure>
+	<type>fa</type>
+	<automaton>
+		<!--The list of states.-->
+		<state id="0" name="q0">
+			<x>79.0</x>
+			<y>146.0</y>
+			<initial/>
+		</state>
+		<state id="1" name="q1">
+			<x>201.0</x>
+			<y>146.0</y>
+		</state>
+		<state id="2" name="q2">
+			<x>323.0</x>
+			<y>146.0</y>
+			<final/>
+		</state>
+		<state id="3" name="q3">
+			<x>445.0</x>
+			<y>146.0</y>
+			<final/>
+		</state>
+		<!--The list of transitions.-->
+		<transition>
+			<from>0</from>
+			<to>0</to>
+			<read>a</read>
+		</transition>
+		<transition>
+			<from>0</from>
+			<to>0</to>
+			<read>b</read>
+		</transition>
+		<transition>
+			<from>0</from>
+			<to>1</to>
+			<read>a</read>
+		</transition>
+		<transition>
+			<from>1</from>
+			<to>2</to>
+			<read>b</read>
+		</transition>
+		<transition>
+			<from>2</from>
+			<to>2</to>
+			<read>a</read>
+		</transition>
+		<transition>
+			<from>2</from>
+			<to>2</to>
+			<read>b</read>
+		</transition>
+		<transition>
+			<from>2</from>
+			<to>3</to>
+			<read/>
+		</transition>
+	</automaton>
+</structure>"#;
+
+    #[test]
+    fn import_nfa() {
+        let nfa = NFA::from_jflap_str(CONTAINS_AB).unwrap();
+        assert_eq!(nfa.states(), &hashset! {0, 1, 2, 3});
+        assert_eq!(nfa.start_state(), 0);
+        assert_eq!(nfa.accepted_states(), &hashset! {2, 3});
+        assert_eq!(nfa.transition(2, None), hashset! {3});
+        assert!(!nfa.is_deterministic());
+
+        let dfa = nfa.to_dfa().minimized();
+        assert_eq!(dfa.states().len(), 3);
+        for (string, accepted) in [("", false), ("ba", false), ("ab", true), ("bbaab", true)].iter()
+        {
+            assert_eq!(nfa.accept(string.chars()), *accepted);
+            assert_eq!(dfa.accept(string.chars()), *accepted);
+        }
+
+        match DFA::from_jflap_str(CONTAINS_AB) {
+            Err(JflapError::InvalidStructure(_)) => {}
+            res => panic!("Expected invalid structure error, got {:?}", res.err()),
+        }
+    }
+
+    #[test]
+    fn import_partial_dfa() {
+        // Only accepts "ab", JFLAP leaves out the transitions into the trap state.
+        let jff = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<structure>
+	<type>fa</type>
+	<automaton>
+		<state id="0" name="q0"><x>0.0</x><y>0.0</y><initial/></state>
+		<state id="1" name="q1"><x>100.0</x><y>0.0</y></state>
+		<state id="2" name="q2"><x>200.0</x><y>0.0</y><final/></state>
+		<transition><from>0</from><to>1</to><read>a</read></transition>
+		<transition><from>1</from><to>2</to><read>b</read></transition>
+	</automaton>
+</structure>"#;
+        let dfa = DFA::from_jflap_str(jff).unwrap();
+        assert_eq!(dfa.states(), &hashset! {0, 1, 2, 3});
+        assert_eq!(dfa.transition(0, Some('b')), hashset! {3});
+        assert_eq!(dfa.transition(3, Some('a')), hashset! {3});
+        assert!(dfa.accept("ab".chars()));
+        assert!(!dfa.accept("aba".chars()));
+        assert!(!dfa.accept("b".chars()));
+    }
+
+    #[test]
+    fn export_round_trip() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {0, 2, 4},
+            hashmap! {
+                0 => hashmap!{
+                    Some('0')=> hashset!{1}
+                },
+                1 => hashmap!{
+                    Some('1')=> hashset!{2},
+                    None => hashset!{3}
+                },
+                2 => hashmap!{
+                    Some('0')=> hashset!{2},
+                    Some('1')=> hashset!{1}
+                },
+                3 => hashmap!{
+                    Some('0') => hashset!{4},
+                }
+            },
+        );
+        let jff = to_jflap_string(&nfa);
+        assert!(jff.contains("<read/>"));
+        let imported = NFA::from_jflap_str(&jff).unwrap();
+        assert_eq!(imported.states().len(), 5);
+        assert_eq!(imported.accepted_states().len(), 3);
+        for string in ["", "0", "00", "01", "001", "010", "0100", "0101", "01011"].iter() {
+            assert_eq!(imported.accept(string.chars()), nfa.accept(string.chars()));
+        }
+
+        let dfa = pre_defined_fa::bin_str_div_by_3();
+        assert_eq!(to_jflap_string(&dfa), to_jflap_string(&dfa));
+        let imported = DFA::from_jflap_str(&to_jflap_string(&dfa)).unwrap();
+        assert_eq!(imported.states().len(), 3);
+    }
+
+    #[test]
+    fn whitespace_round_trip() {
+        let dfa: DFA<char, usize> = DFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {
+                0 => hashmap!{' ' => 1, '\t' => 1, 'a' => 0},
+                1 => hashmap!{' ' => 1, '\t' => 0, 'a' => 0},
+            },
+        );
+        let jff = to_jflap_string(&dfa);
+        assert!(jff.contains("<read> </read>"));
+        let imported = DFA::from_jflap_str(&jff).unwrap();
+        assert_eq!(imported.alphabets(), dfa.alphabets());
+        for string in ["", " ", "\t", "a ", " \t", "  ", "a\t "].iter() {
+            assert_eq!(
+                imported.accept(string.chars()),
+                dfa.accept(string.chars()),
+                "{:?}",
+                string
+            );
+        }
+    }
+
+    #[test]
+    fn cdata_read() {
+        let jff = CONTAINS_AB.replace("<read/>", "<read><![CDATA[<]]></read>");
+        let nfa = NFA::from_jflap_str(&jff).unwrap();
+        assert!(nfa.alphabets().contains(&'<'));
+        assert!(nfa.accept("ab".chars()));
+        assert!(!nfa.accept("".chars()));
+        assert!(nfa.accept("ab<".chars()));
+    }
+
+    #[test]
+    fn unsupported_files() {
+        let multi_char = CONTAINS_AB.replace("<read>a</read>", "<read>ab</read>");
+        assert!(matches!(
+            NFA::from_jflap_str(&multi_char),
+            Err(JflapError::InvalidStructure(_))
+        ));
+        let turing = CONTAINS_AB.replace("<type>fa</type>", "<type>turing</type>");
+        assert!(matches!(
+            NFA::from_jflap_str(&turing),
+            Err(JflapError::InvalidStructure(_))
+        ));
+        assert!(matches!(
+            NFA::from_jflap_str("<structure><type>fa</type>"),
+            Err(JflapError::InvalidStructure(_)) | Err(JflapError::Xml(_))
+        ));
+        let duplicates = [
+            r#"<state id="1" name="q1"/>"#,
+            r#"<state id="1" name="q4"><x>0.0</x><y>0.0</y></state>"#,
+        ];
+        for duplicate in duplicates.iter() {
+            let jff = CONTAINS_AB.replace(
+                "</state>\n\t\t<!--The list of transitions.-->",
+                &format!("</state>{}", duplicate),
+            );
+            assert_ne!(jff, CONTAINS_AB);
+            match NFA::from_jflap_str(&jff) {
+                Err(JflapError::InvalidStructure(message)) => {
+                    assert_eq!(message, "duplicate state ID 1")
+                }
+                other => panic!(
+                    "unexpected result {:?}",
+                    other.map(|nfa| nfa.states().len())
+                ),
+            }
+        }
+        let undeclared_state = CONTAINS_AB.replace("<to>3</to>", "<to>7</to>");
+        assert!(matches!(
+            NFA::from_jflap_str(&undeclared_state),
+            Err(JflapError::InvalidAutomaton(
+                InvalidAutomatonError::UnknownTransitionStates { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use rand::Rng;
+    use std::collections::HashMap;
+    use token_scanner::automaton::lazy_dfa::{LazyDFA, LazyDFAOptions, LazyDFAStats};
+    use token_scanner::{FiniteAutomaton, NFA};
+
+    /// `(a|b)*a(a|b){n}`, whose smallest DFA has `2^(n + 1)` states.
+    fn nth_from_last_is_a(n: usize) -> NFA<char, usize> {
+        let mut transition_map = hashmap! {
+            0 => hashmap! {Some('a') => hashset! {0, 1}, Some('b') => hashset! {0}},
+        };
+        for state in 1..=n {
+            transition_map.insert(
+                state,
+                hashmap! {Some('a') => hashset! {state + 1}, Some('b') => hashset! {state + 1}},
+            );
+        }
+        NFA::from_map(0, hashset! {n + 1}, transition_map)
+    }
+
+    fn random_input<R: Rng>(rng: &mut R, len: usize) -> String {
+        (0..len)
+            .map(|_| if rng.gen::<bool>() { 'a' } else { 'b' })
+            .collect()
+    }
+
+    fn expected(input: &str, n: usize) -> bool {
+        input.len() > n && input.as_bytes()[input.len() - n - 1] == b'a'
+    }
+
+    #[test]
+    fn matches_eager_dfa() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {3},
+            hashmap! {
+                0 => hashmap! {Some('a') => hashset! {1}, None => hashset! {2}},
+                1 => hashmap! {Some('b') => hashset! {1, 3}},
+                2 => hashmap! {Some('c') => hashset! {3}, Some('a') => hashset! {0}},
+                3 => hashmap! {None => hashset! {0}},
+            },
+        );
+        let dfa = nfa.to_dfa();
+        let mut lazy = LazyDFA::new(&nfa);
+        let mut rng = rand::thread_rng();
+        for _ in 0..300 {
+            let len = rng.gen_range(0, 8);
+            let input: String = (0..len)
+                .map(|_| ['a', 'b', 'c'][rng.gen_range(0, 3)])
+                .collect();
+            assert_eq!(
+                lazy.accept(input.chars()),
+                dfa.accept(input.chars()),
+                "{}",
+                input
+            );
+        }
+        assert!(lazy.cached_states() <= dfa.states().len());
+        assert!(!lazy.accept("abx".chars()));
+    }
+
+    #[test]
+    fn exponential_pattern() {
+        let n = 20;
+        let mut lazy = LazyDFA::new(&nth_from_last_is_a(n));
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let input = random_input(&mut rng, 200);
+            assert_eq!(lazy.accept(input.chars()), expected(&input, n));
+        }
+        assert!(lazy.cached_states() < 4200);
+        assert_eq!(lazy.stats(), LazyDFAStats::default());
+    }
+
+    #[test]
+    fn bounded_cache() {
+        let n = 12;
+        let options = LazyDFAOptions {
+            cache_capacity: 16 * 1024,
+            max_cache_clears: 3,
+        };
+        let mut lazy = LazyDFA::with_options(&nth_from_last_is_a(n), options.clone());
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let input = random_input(&mut rng, 2000);
+            assert_eq!(lazy.accept(input.chars()), expected(&input, n));
+            assert!(lazy.memory_usage() <= options.cache_capacity);
+        }
+        let stats = lazy.stats();
+        assert!(stats.cache_clears > 0);
+        assert!(stats.nfa_fallbacks > 0);
+
+        // Short inputs are served by the cache without falling back.
+        let mut results = HashMap::new();
+        let fallbacks = lazy.stats().nfa_fallbacks;
+        for _ in 0..50 {
+            let input = random_input(&mut rng, 14);
+            results.insert(input.clone(), lazy.accept(input.chars()));
+        }
+        for (input, accepted) in results {
+            assert_eq!(accepted, expected(&input, n));
+        }
+        assert_eq!(lazy.stats().nfa_fallbacks, fallbacks);
+    }
+}
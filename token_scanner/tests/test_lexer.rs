@@ -0,0 +1,301 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use token_scanner::lexer::{
+        LexError, LexerSpec, Pattern, RecoveryStrategy, SourceMap, Span, Token, TokenKind,
+    };
+    use token_scanner::pre_defined_fa;
+    use token_scanner::unicode::char_automaton::CharAutomaton;
+    use token_scanner::unicode::char_class::{CharClass, CharRange};
+    use token_scanner::{FiniteAutomaton, NFA};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Kind {
+        If,
+        Ident,
+        Number,
+        Op,
+        Space,
+    }
+
+    fn class(start: char, end: char) -> Pattern {
+        Pattern::class(CharClass::from(CharRange::new(start, end)))
+    }
+
+    fn spec() -> LexerSpec<Kind> {
+        let mut spec = LexerSpec::new();
+        spec.rule(Kind::If, Pattern::literal("if"))
+            .rule(
+                Kind::Ident,
+                Pattern::concat(vec![
+                    Pattern::alternation(vec![class('a', 'z'), class('α', 'ω')]),
+                    Pattern::alternation(vec![class('a', 'z'), class('α', 'ω'), class('0', '9')])
+                        .star(),
+                ]),
+            )
+            .rule(Kind::Number, class('0', '9').plus())
+            .rule(
+                Kind::Op,
+                Pattern::alternation(vec![
+                    Pattern::literal("="),
+                    Pattern::literal("=="),
+                    Pattern::literal("<"),
+                    Pattern::literal("<="),
+                ]),
+            )
+            .rule(Kind::Space, Pattern::literal(" ").plus());
+        spec
+    }
+
+    fn kinds_and_lexemes(input: &str) -> Vec<(Kind, String)> {
+        let lexer = spec().compile();
+        lexer
+            .lex(input)
+            .map(|token| {
+                let token = token.unwrap();
+                (token.kind, token.lexeme.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        assert_eq!(
+            kinds_and_lexemes("iffy==10<=if"),
+            vec![
+                (Kind::Ident, "iffy".to_string()),
+                (Kind::Op, "==".to_string()),
+                (Kind::Number, "10".to_string()),
+                (Kind::Op, "<=".to_string()),
+                (Kind::If, "if".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn earliest_rule_wins_ties() {
+        let mut spec = LexerSpec::new();
+        spec.rule("KEYWORD", Pattern::literal("if"))
+            .rule("IDENT", class('a', 'z').plus());
+        let lexer = spec.compile();
+        let kinds: Vec<&str> = lexer.lex("if").map(|token| token.unwrap().kind).collect();
+        assert_eq!(kinds, vec!["KEYWORD"]);
+
+        let mut spec = LexerSpec::new();
+        spec.rule("IDENT", class('a', 'z').plus())
+            .rule("KEYWORD", Pattern::literal("if"));
+        let lexer = spec.compile();
+        let kinds: Vec<&str> = lexer.lex("if").map(|token| token.unwrap().kind).collect();
+        assert_eq!(kinds, vec!["IDENT"]);
+    }
+
+    #[test]
+    fn spans_are_byte_offsets() {
+        let lexer = spec().compile();
+        let tokens: Vec<Token<Kind>> = lexer.lex("αβ = x1").map(Result::unwrap).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    kind: Kind::Ident,
+                    lexeme: "αβ",
+                    span: Span::new(0, 4),
+                },
+                Token {
+                    kind: Kind::Space,
+                    lexeme: " ",
+                    span: Span::new(4, 5),
+                },
+                Token {
+                    kind: Kind::Op,
+                    lexeme: "=",
+                    span: Span::new(5, 6),
+                },
+                Token {
+                    kind: Kind::Space,
+                    lexeme: " ",
+                    span: Span::new(6, 7),
+                },
+                Token {
+                    kind: Kind::Ident,
+                    lexeme: "x1",
+                    span: Span::new(7, 9),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        let lexer = spec().compile();
+        let tokens: Vec<Result<Token<Kind>, LexError>> = lexer.lex("x #y").collect();
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[2], Err(LexError { position: 2 }));
+        assert_eq!(
+            tokens[2].as_ref().unwrap_err().to_string(),
+            "no rule matches at byte 2"
+        );
+    }
+
+    #[test]
+    fn patterns() {
+        let pattern = Pattern::literal("ab").repeat(2, Some(3));
+        let nfa = pattern.to_nfa();
+        assert!(!nfa.accept_chars("ab".chars()));
+        assert!(nfa.accept_chars("abab".chars()));
+        assert!(nfa.accept_chars("ababab".chars()));
+        assert!(!nfa.accept_chars("abababab".chars()));
+
+        let pattern = Pattern::concat(vec![
+            Pattern::literal("-").optional(),
+            Pattern::automaton(&pre_defined_fa::bin_str_div_by_3()),
+        ]);
+        let nfa = pattern.to_nfa();
+        assert!(nfa.accept_chars("-110".chars()));
+        assert!(nfa.accept_chars("11".chars()));
+        assert!(!nfa.accept_chars("-100".chars()));
+        assert!(!nfa.accept_chars("--11".chars()));
+
+        // States of an automaton pattern need not be numbered `0..n`.
+        let ab = NFA::from_map(
+            10,
+            hashset! {20},
+            hashmap! {
+                10 => hashmap! {Some(CharClass::from('a')) => hashset! {15}},
+                15 => hashmap! {Some(CharClass::from('b')) => hashset! {20}},
+            },
+        );
+        let nfa = Pattern::concat(vec![Pattern::Automaton(ab), Pattern::literal("!")]).to_nfa();
+        assert_eq!(nfa.states().len(), 7);
+        assert!(nfa.states().iter().all(|state| *state < 7));
+        assert!(nfa.accept_chars("ab!".chars()));
+        assert!(!nfa.accept_chars("ab".chars()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn repeat_maximum_below_minimum() {
+        Pattern::literal("a").repeat(3, Some(1));
+    }
+
+    #[test]
+    fn recovery_strategies() {
+        let lexer = spec().compile();
+        let input = "x #$y 1";
+        let lex = |strategy| {
+            lexer
+                .lex_with_recovery(input, strategy)
+                .map(|token| (token.kind, token.lexeme))
+                .collect::<Vec<_>>()
+        };
+        let space = (TokenKind::Rule(Kind::Space), " ");
+        assert_eq!(
+            lex(RecoveryStrategy::SkipChar),
+            vec![
+                (TokenKind::Rule(Kind::Ident), "x"),
+                space,
+                (TokenKind::Error, "#"),
+                (TokenKind::Error, "$"),
+                (TokenKind::Rule(Kind::Ident), "y"),
+                space,
+                (TokenKind::Rule(Kind::Number), "1"),
+            ]
+        );
+        assert_eq!(
+            lex(RecoveryStrategy::SkipToWhitespace),
+            vec![
+                (TokenKind::Rule(Kind::Ident), "x"),
+                space,
+                (TokenKind::Error, "#$y"),
+                space,
+                (TokenKind::Rule(Kind::Number), "1"),
+            ]
+        );
+        assert_eq!(
+            lex(RecoveryStrategy::PanicMode),
+            vec![
+                (TokenKind::Rule(Kind::Ident), "x"),
+                space,
+                (TokenKind::Error, "#$"),
+                (TokenKind::Rule(Kind::Ident), "y"),
+                space,
+                (TokenKind::Rule(Kind::Number), "1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn panic_mode_resyncs_where_a_rule_can_start() {
+        let mut spec = LexerSpec::new();
+        spec.rule("ARROW", Pattern::literal("->"))
+            .rule("SPACE", Pattern::literal(" "));
+        let lexer = spec.compile();
+        // `-` starts the arrow rule, so recovery stops before it even though `-x` is no token.
+        let tokens: Vec<(TokenKind<&str>, &str)> = lexer
+            .lex_with_recovery("#-x ->", RecoveryStrategy::PanicMode)
+            .map(|token| (token.kind, token.lexeme))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (TokenKind::Error, "#"),
+                (TokenKind::Error, "-x"),
+                (TokenKind::Rule("SPACE"), " "),
+                (TokenKind::Rule("ARROW"), "->"),
+            ]
+        );
+    }
+
+    #[test]
+    fn recovery_diagnostics() {
+        let lexer = spec().compile();
+        let input = "x = 1\n@y = ~~\t";
+        let mut tokens = lexer.lex_with_recovery(input, RecoveryStrategy::PanicMode);
+        let mut end = 0;
+        for token in tokens.by_ref() {
+            assert_eq!(token.span.start, end);
+            end = token.span.end;
+        }
+        assert_eq!(end, input.len());
+
+        let map = SourceMap::new(input);
+        let rendered: Vec<String> = tokens
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.render(&map))
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "error: unexpected \"\\n@\"\n --> 1:6\n  |\n1 | x = 1\n  |      ^\n",
+                "error: unexpected \"~~\\t\"\n --> 2:6\n  |\n2 | @y = ~~\t\n  |      ^^^\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_rules() {
+        let mut spec = LexerSpec::new();
+        spec.rule(Kind::Number, class('0', '9').plus())
+            .skip_rule(Kind::Space, Pattern::literal(" ").plus());
+        let lexer = spec.compile();
+        assert!(lexer.is_skipped(1));
+        let lexemes: Vec<&str> = lexer
+            .lex(" 1  23 ")
+            .map(|token| token.unwrap().lexeme)
+            .collect();
+        assert_eq!(lexemes, vec!["1", "23"]);
+        let kinds: Vec<TokenKind<Kind>> = lexer
+            .lex_with_recovery(" 1 x 2", RecoveryStrategy::SkipChar)
+            .map(|token| token.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Rule(Kind::Number),
+                TokenKind::Error,
+                TokenKind::Rule(Kind::Number),
+            ]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+#[cfg(test)]
+mod tests {
+    use token_scanner::lexer::{
+        Action, ModalLexerSpec, Pattern, RecoveryStrategy, TokenKind, UnknownModeError,
+    };
+    use token_scanner::unicode::char_class::{CharClass, CharRange};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Kind {
+        Ident,
+        Space,
+        LBrace,
+        RBrace,
+        Quote,
+        StringText,
+        InterpolationStart,
+        CommentStart,
+        CommentEnd,
+        CommentText,
+    }
+
+    fn push(mode: &str) -> Action {
+        Action::Push(mode.to_string())
+    }
+
+    fn letters() -> Pattern {
+        Pattern::class(CharClass::from(CharRange::new('a', 'z'))).plus()
+    }
+
+    /// Identifiers, nested block comments and strings with `${...}` interpolation.
+    fn spec() -> ModalLexerSpec<Kind> {
+        let mut spec = ModalLexerSpec::new();
+        spec.mode("code")
+            .rule(Kind::Ident, letters())
+            .rule(Kind::Space, Pattern::literal(" ").plus())
+            .rule_with_action(Kind::LBrace, Pattern::literal("{"), push("code"))
+            .rule_with_action(Kind::RBrace, Pattern::literal("}"), Action::Pop)
+            .rule_with_action(Kind::Quote, Pattern::literal("\""), push("string"))
+            .rule_with_action(Kind::CommentStart, Pattern::literal("/*"), push("comment"));
+        spec.mode("string")
+            .rule_with_action(Kind::Quote, Pattern::literal("\""), Action::Pop)
+            .rule_with_action(
+                Kind::InterpolationStart,
+                Pattern::literal("${"),
+                push("code"),
+            )
+            .rule(
+                Kind::StringText,
+                Pattern::class(
+                    CharClass::any()
+                        .difference(&CharClass::from('"'))
+                        .difference(&CharClass::from('$')),
+                )
+                .plus(),
+            )
+            .rule(Kind::StringText, Pattern::literal("$"));
+        spec.mode("comment")
+            .rule_with_action(Kind::CommentStart, Pattern::literal("/*"), push("comment"))
+            .rule_with_action(Kind::CommentEnd, Pattern::literal("*/"), Action::Pop)
+            .rule(Kind::CommentText, Pattern::class(CharClass::any()));
+        spec
+    }
+
+    fn lex(input: &str) -> Vec<(Kind, String)> {
+        let lexer = spec().compile().unwrap();
+        lexer
+            .lex(input)
+            .map(|token| {
+                let token = token.unwrap();
+                (token.kind, token.lexeme.to_string())
+            })
+            .collect()
+    }
+
+    fn token(kind: Kind, lexeme: &str) -> (Kind, String) {
+        (kind, lexeme.to_string())
+    }
+
+    #[test]
+    fn nested_comments() {
+        let tokens = lex("a/* x /* y */ z */b");
+        let kinds: Vec<Kind> = tokens.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds.first(), Some(&Kind::Ident));
+        assert_eq!(
+            kinds
+                .iter()
+                .filter(|kind| **kind == Kind::CommentStart)
+                .count(),
+            2
+        );
+        assert_eq!(tokens.last(), Some(&token(Kind::Ident, "b")));
+        assert_eq!(tokens[tokens.len() - 2], token(Kind::CommentEnd, "*/"));
+    }
+
+    #[test]
+    fn string_interpolation() {
+        assert_eq!(
+            lex("x \"a $ ${y {z} \"w\"} b\" c"),
+            vec![
+                token(Kind::Ident, "x"),
+                token(Kind::Space, " "),
+                token(Kind::Quote, "\""),
+                token(Kind::StringText, "a "),
+                token(Kind::StringText, "$"),
+                token(Kind::StringText, " "),
+                token(Kind::InterpolationStart, "${"),
+                token(Kind::Ident, "y"),
+                token(Kind::Space, " "),
+                token(Kind::LBrace, "{"),
+                token(Kind::Ident, "z"),
+                token(Kind::RBrace, "}"),
+                token(Kind::Space, " "),
+                token(Kind::Quote, "\""),
+                token(Kind::StringText, "w"),
+                token(Kind::Quote, "\""),
+                token(Kind::RBrace, "}"),
+                token(Kind::StringText, " b"),
+                token(Kind::Quote, "\""),
+                token(Kind::Space, " "),
+                token(Kind::Ident, "c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn switch_and_pop_in_initial_mode() {
+        let mut spec = ModalLexerSpec::new();
+        spec.mode("a")
+            .rule_with_action(
+                "TO_B",
+                Pattern::literal("!"),
+                Action::Switch("b".to_string()),
+            )
+            .rule_with_action("POP", Pattern::literal("."), Action::Pop)
+            .rule("A", Pattern::literal("x"));
+        spec.mode("b")
+            .rule_with_action(
+                "TO_A",
+                Pattern::literal("!"),
+                Action::Switch("a".to_string()),
+            )
+            .rule("B", Pattern::literal("x"));
+        let lexer = spec.compile().unwrap();
+        let mut tokens = lexer.lex(".x!x!x");
+        let mut kinds = vec![];
+        let mut modes = vec![];
+        while let Some(token) = tokens.next() {
+            kinds.push(token.unwrap().kind);
+            modes.push(tokens.mode());
+        }
+        assert_eq!(kinds, vec!["POP", "A", "TO_B", "B", "TO_A", "A"]);
+        assert_eq!(modes, vec![0, 0, 1, 1, 0, 0]);
+        assert_eq!(lexer.mode_names(), &["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn recovery_keeps_mode() {
+        let lexer = spec().compile().unwrap();
+        let kinds: Vec<TokenKind<Kind>> = lexer
+            .lex_with_recovery("{a#}#", RecoveryStrategy::SkipChar)
+            .map(|token| token.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Rule(Kind::LBrace),
+                TokenKind::Rule(Kind::Ident),
+                TokenKind::Error,
+                TokenKind::Rule(Kind::RBrace),
+                TokenKind::Error,
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_mode() {
+        let mut spec = spec();
+        spec.mode("comment")
+            .rule_with_action(Kind::Quote, Pattern::literal("\""), push("strng"));
+        let err = spec.compile().unwrap_err();
+        assert_eq!(
+            err,
+            UnknownModeError {
+                mode: "strng".to_string(),
+                in_mode: "comment".to_string(),
+                rule: 3,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "rule 3 of mode \"comment\" refers to unknown mode \"strng\""
+        );
+    }
+}
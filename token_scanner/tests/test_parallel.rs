@@ -0,0 +1,67 @@
+#![cfg(feature = "rayon")]
+
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use rand::Rng;
+    use std::collections::{HashMap, HashSet};
+    use token_scanner::{FiniteAutomaton, NFA};
+
+    fn assert_same_dfa(nfa: &NFA<char, usize>) {
+        let sequential = nfa.to_dfa().relabeled();
+        let parallel = nfa.to_dfa_parallel().relabeled();
+        assert_eq!(parallel.states(), sequential.states());
+        assert_eq!(parallel.accepted_states(), sequential.accepted_states());
+        assert_eq!(parallel.transition_map(), sequential.transition_map());
+    }
+
+    #[test]
+    fn exponential_pattern() {
+        // (a|b)*a(a|b){n}
+        let n = 9;
+        let mut transition_map = hashmap! {
+            0 => hashmap! {Some('a') => hashset! {0, 1}, Some('b') => hashset! {0}},
+        };
+        for state in 1..=n {
+            transition_map.insert(
+                state,
+                hashmap! {Some('a') => hashset! {state + 1}, Some('b') => hashset! {state + 1}},
+            );
+        }
+        let nfa = NFA::from_map(0, hashset! {n + 1}, transition_map);
+        assert_eq!(nfa.to_dfa_parallel().states().len(), 1 << (n + 1));
+        assert_same_dfa(&nfa);
+    }
+
+    #[test]
+    fn random_nfas() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let state_count = rng.gen_range(1, 12);
+            let mut transition_map: HashMap<usize, HashMap<Option<char>, HashSet<usize>>> =
+                HashMap::new();
+            for state in 0..state_count {
+                for alphabet in [None, Some('a'), Some('b'), Some('c')] {
+                    let to_states: HashSet<usize> = (0..state_count)
+                        .filter(|_| rng.gen_range(0, 4) == 0)
+                        .collect();
+                    if !to_states.is_empty() {
+                        transition_map
+                            .entry(state)
+                            .or_default()
+                            .insert(alphabet, to_states);
+                    }
+                }
+            }
+            let accepted_states = (0..state_count).filter(|_| rng.gen::<bool>()).collect();
+            let nfa = NFA::from_formal(
+                (0..state_count).collect(),
+                hashset! {'a', 'b', 'c'},
+                0,
+                accepted_states,
+                transition_map,
+            );
+            assert_same_dfa(&nfa);
+        }
+    }
+}
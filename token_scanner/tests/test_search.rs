@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use rand::Rng;
+    use token_scanner::{FiniteAutomaton, NFA};
+
+    /// "abcd", or "c", or "b" followed by any number of "a".
+    fn nfa() -> NFA<char, usize> {
+        NFA::from_map(
+            0,
+            hashset! {4, 5, 6},
+            hashmap! {
+                0 => hashmap! {
+                    Some('a') => hashset! {1},
+                    Some('c') => hashset! {5},
+                    Some('b') => hashset! {6},
+                },
+                1 => hashmap! {Some('b') => hashset! {2}},
+                2 => hashmap! {Some('c') => hashset! {3}},
+                3 => hashmap! {Some('d') => hashset! {4}},
+                6 => hashmap! {Some('a') => hashset! {6}},
+            },
+        )
+    }
+
+    fn overlapping_by_brute_force(nfa: &NFA<char, usize>, text: &[char]) -> Vec<(usize, usize)> {
+        let mut res = vec![];
+        for end in 0..=text.len() {
+            for start in 0..=end {
+                if nfa.accept(text[start..end].iter().cloned()) {
+                    res.push((start, end));
+                }
+            }
+        }
+        res
+    }
+
+    fn find_by_brute_force(
+        nfa: &NFA<char, usize>,
+        text: &[char],
+        from: usize,
+    ) -> Option<(usize, usize)> {
+        let spans = overlapping_by_brute_force(nfa, text);
+        let start = spans
+            .iter()
+            .filter(|(start, _)| *start >= from)
+            .map(|(start, _)| *start)
+            .min()?;
+        spans
+            .into_iter()
+            .filter(|(span_start, _)| *span_start == start)
+            .max_by_key(|(_, end)| *end)
+    }
+
+    #[test]
+    fn leftmost_longest() {
+        let nfa = nfa();
+        // "c" ends first, but "abcd" starts first.
+        assert_eq!(nfa.find("xabcd".chars()), Some((1, 5)));
+        assert_eq!(nfa.find("xabcx".chars()), Some((2, 3)));
+        assert_eq!(nfa.find("xaxcx".chars()), Some((3, 4)));
+        assert_eq!(nfa.find("xbaaac".chars()), Some((1, 5)));
+        assert_eq!(nfa.find("xyz".chars()), None);
+
+        let spans: Vec<(usize, usize)> = nfa.find_iter("abcdbacc".chars()).collect();
+        assert_eq!(spans, vec![(0, 4), (4, 6), (6, 7), (7, 8)]);
+
+        assert_eq!(
+            nfa.find_overlapping("abcd".chars()),
+            vec![(1, 2), (2, 3), (0, 4)]
+        );
+    }
+
+    #[test]
+    fn empty_matches() {
+        // Any number of "a".
+        let nfa = NFA::from_map(
+            0,
+            hashset! {0},
+            hashmap! {0 => hashmap! {Some('a') => hashset! {0}}},
+        );
+        let spans: Vec<(usize, usize)> = nfa.find_iter("baab".chars()).collect();
+        assert_eq!(spans, vec![(0, 0), (1, 3), (3, 3), (4, 4)]);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let nfa = nfa();
+        let dfa = nfa.to_dfa();
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let len = rng.gen_range(0, 12);
+            let text: Vec<char> = (0..len)
+                .map(|_| ['a', 'b', 'c', 'd', 'x'][rng.gen_range(0, 5)])
+                .collect();
+            let overlapping = overlapping_by_brute_force(&nfa, &text);
+            assert_eq!(nfa.find_overlapping(text.iter().cloned()), overlapping);
+            assert_eq!(dfa.find_overlapping(text.iter().cloned()), overlapping);
+
+            let expected = find_by_brute_force(&nfa, &text, 0);
+            assert_eq!(nfa.find(text.iter().cloned()), expected);
+            assert_eq!(dfa.find(text.iter().cloned()), expected);
+
+            let mut expected = vec![];
+            let mut from = 0;
+            while let Some((start, end)) = find_by_brute_force(&nfa, &text, from) {
+                expected.push((start, end));
+                from = end;
+            }
+            let spans: Vec<(usize, usize)> = dfa.find_iter(text.iter().cloned()).collect();
+            assert_eq!(spans, expected);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "serde")]
+
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use token_scanner::{pre_defined_fa, CommunicativeHashSet, FiniteAutomaton, DFA, NFA};
+
+    fn nfa_1() -> NFA<char, i32> {
+        NFA::from_map(
+            0,
+            hashset! {0, 2, 4},
+            hashmap! {
+                0 => hashmap!{
+                    Some('0')=> hashset!{1}
+                },
+                1 => hashmap!{
+                    Some('1')=> hashset!{2},
+                    None => hashset!{3}
+                },
+                2 => hashmap!{
+                    Some('0')=> hashset!{2},
+                    Some('1')=> hashset!{1}
+                },
+                3 => hashmap!{
+                    Some('0') => hashset!{4},
+                }
+            },
+        )
+    }
+
+    #[test]
+    fn nfa_round_trip() {
+        let nfa = nfa_1();
+        let json = serde_json::to_string(&nfa).unwrap();
+        assert_eq!(json, serde_json::to_string(&nfa_1()).unwrap());
+        let deserialized: NFA<char, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.states(), nfa.states());
+        assert_eq!(deserialized.alphabets(), nfa.alphabets());
+        assert_eq!(deserialized.start_state(), nfa.start_state());
+        assert_eq!(deserialized.accepted_states(), nfa.accepted_states());
+        for state in nfa.states() {
+            assert_eq!(
+                deserialized.transition(*state, None),
+                nfa.transition(*state, None)
+            );
+            for alphabet in nfa.alphabets() {
+                assert_eq!(
+                    deserialized.transition(*state, Some(*alphabet)),
+                    nfa.transition(*state, Some(*alphabet))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dfa_round_trip() {
+        let dfa = nfa_1().to_dfa().minimized();
+        let json = serde_json::to_string_pretty(&dfa).unwrap();
+        let deserialized: DFA<char, CommunicativeHashSet<CommunicativeHashSet<i32>>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.states(), dfa.states());
+        assert_eq!(deserialized.accepted_states(), dfa.accepted_states());
+        for string in ["", "0", "00", "01", "001", "010", "0100", "0101", "01011"].iter() {
+            assert_eq!(
+                deserialized.accept(string.chars()),
+                dfa.accept(string.chars())
+            );
+        }
+    }
+
+    #[test]
+    fn dfa_json_fixture() {
+        let json = r#"{
+            "states": [0, 1, 2],
+            "alphabets": ["0", "1"],
+            "start_state": 0,
+            "accepted_states": [0],
+            "transitions": [
+                {"from": 0, "alphabet": "0", "to": 0},
+                {"from": 0, "alphabet": "1", "to": 1},
+                {"from": 1, "alphabet": "0", "to": 2},
+                {"from": 1, "alphabet": "1", "to": 0},
+                {"from": 2, "alphabet": "0", "to": 1},
+                {"from": 2, "alphabet": "1", "to": 2}
+            ]
+        }"#;
+        let dfa: DFA<char, u8> = serde_json::from_str(json).unwrap();
+        let expected = pre_defined_fa::bin_str_div_by_3();
+        assert_eq!(
+            serde_json::to_value(&dfa).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_automata_are_rejected() {
+        let missing_transition = r#"{
+            "states": [0, 1],
+            "alphabets": ["0"],
+            "start_state": 0,
+            "accepted_states": [1],
+            "transitions": [{"from": 0, "alphabet": "0", "to": 1}]
+        }"#;
+        assert!(
+            serde_json::from_str::<NFA<char, u8>>(&missing_transition.replace("1}", "[1]}"))
+                .is_ok()
+        );
+        let err = serde_json::from_str::<DFA<char, u8>>(missing_transition)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("missing transition"), "{}", err);
+
+        let unknown_start_state = r#"{
+            "states": [0],
+            "alphabets": [],
+            "start_state": 1,
+            "accepted_states": [],
+            "transitions": []
+        }"#;
+        assert!(serde_json::from_str::<NFA<char, u8>>(unknown_start_state).is_err());
+    }
+
+    #[test]
+    fn communicative_hash_set_round_trip() {
+        let set = CommunicativeHashSet::from(hashset! {3, 1, 2});
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, "[1,2,3]");
+        let deserialized: CommunicativeHashSet<i32> = serde_json::from_str(&json).unwrap();
+        assert!(deserialized == set);
+    }
+}
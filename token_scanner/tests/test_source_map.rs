@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use token_scanner::lexer::{LexerSpec, Pattern, Position, SourceMap, Span};
+    use token_scanner::unicode::char_class::CharClass;
+
+    #[test]
+    fn positions() {
+        let source = "a\r\nβ😀c\n\nend";
+        let map = SourceMap::new(source);
+        assert_eq!(map.line_count(), 4);
+        assert_eq!(map.line(1), "a");
+        assert_eq!(map.line(2), "β😀c");
+        assert_eq!(map.line(3), "");
+        assert_eq!(map.line(4), "end");
+
+        // 'c' after a 2-byte and a 4-byte character, the latter being 2 UTF-16 code units.
+        let offset = source.find('c').unwrap();
+        assert_eq!(
+            map.position(offset),
+            Position {
+                offset,
+                line: 2,
+                column: 3,
+                utf8_column: 7,
+                utf16_column: 4,
+            }
+        );
+        assert_eq!(map.position(1).column, 2);
+        assert_eq!(map.position(source.len()).line, 4);
+        assert_eq!(map.position(source.len()).column, 4);
+        assert_eq!(map.position(source.len()).to_string(), "4:4");
+    }
+
+    #[test]
+    fn located_tokens_match_source_map() {
+        let mut spec = LexerSpec::new();
+        spec.rule(
+            "WORD",
+            Pattern::class(CharClass::any().difference(&CharClass::from('\n'))).plus(),
+        )
+        .rule("NEWLINE", Pattern::literal("\n"));
+        let lexer = spec.compile();
+        let source = "αβγ\n\nx y\nλ";
+        let map = SourceMap::new(source);
+        let mut count = 0;
+        for located in lexer.lex(source).located() {
+            let (token, location) = located.unwrap();
+            assert_eq!(location, map.location(token.span));
+            count += 1;
+        }
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn snippets() {
+        let map = SourceMap::new("fn main() {\n\tlet s = \"abc\n}\n");
+        // A string literal that is not closed on its line.
+        let start = map.source().find('"').unwrap();
+        assert_eq!(
+            map.render_snippet(Span::new(start, map.source().len()), "unterminated string"),
+            "error: unterminated string\n --> 2:10\n  |\n2 | \tlet s = \"abc\n  | \t        ^^^^\n"
+        );
+        let end = map.source().len();
+        assert_eq!(
+            map.render_snippet(Span::new(end, end), "unexpected end of input"),
+            "error: unexpected end of input\n --> 4:1\n  |\n4 | \n  | ^\n"
+        );
+
+        let source = (1..=10)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let map = SourceMap::new(&source);
+        let start = source.find("10").unwrap();
+        assert_eq!(
+            map.render_snippet(Span::new(start, start + 2), "too large"),
+            "error: too large\n  --> 10:1\n   |\n10 | 10\n   | ^^\n"
+        );
+    }
+}
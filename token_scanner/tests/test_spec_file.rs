@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use token_scanner::lexer::{ModalLexerDFA, ModalLexerSpec, SpecFileError};
+
+    const SPEC: &str = r#"
+# A small language with nested comments and strings.
+digit   [0-9]
+ident   [A-Za-z_]\w*    # letters, digits and underscores
+
+%%
+{digit}+(\.{digit}+)?   NUMBER
+"if"                    IF
+{ident}                 IDENT
+[-+*/=]                 OPERATOR
+\s+                     skip
+"/*"                    skip    push comment
+\"                      QUOTE   switch string
+
+%mode comment
+"/*"                    skip    push comment
+"*/"                    skip    pop
+.|\n                    skip
+
+%mode string
+\"                      QUOTE   switch INITIAL
+\\[n"\\]                ESCAPE
+[^"\\]+                 TEXT
+"#;
+
+    fn lex(spec: &str, input: &str) -> Vec<(String, String)> {
+        let lexer = ModalLexerDFA::from_spec_str(spec).unwrap();
+        lexer
+            .lex(input)
+            .map(|token| {
+                let token = token.unwrap();
+                (token.kind, token.lexeme.to_string())
+            })
+            .collect()
+    }
+
+    fn tokens(tokens: &[(&str, &str)]) -> Vec<(String, String)> {
+        tokens
+            .iter()
+            .map(|(kind, lexeme)| (kind.to_string(), lexeme.to_string()))
+            .collect()
+    }
+
+    fn error(spec: &str) -> SpecFileError {
+        ModalLexerSpec::from_spec_str(spec).unwrap_err()
+    }
+
+    #[test]
+    fn load_and_lex() {
+        let spec = ModalLexerSpec::from_spec_str(SPEC).unwrap();
+        let modes: Vec<&str> = spec.modes().iter().map(|(mode, _)| mode.as_str()).collect();
+        assert_eq!(modes, vec!["INITIAL", "comment", "string"]);
+        assert_eq!(
+            lex(
+                SPEC,
+                "if x1 = 3.25 /* a /* b */ c */ + iffy\n\"say \\\"hi\\\"\\n\" 7"
+            ),
+            tokens(&[
+                ("IF", "if"),
+                ("IDENT", "x1"),
+                ("OPERATOR", "="),
+                ("NUMBER", "3.25"),
+                ("OPERATOR", "+"),
+                ("IDENT", "iffy"),
+                ("QUOTE", "\""),
+                ("TEXT", "say "),
+                ("ESCAPE", "\\\""),
+                ("TEXT", "hi"),
+                ("ESCAPE", "\\\""),
+                ("ESCAPE", "\\n"),
+                ("QUOTE", "\""),
+                ("NUMBER", "7"),
+            ])
+        );
+    }
+
+    #[test]
+    fn pattern_syntax() {
+        let spec = r#"
+%%
+a{2,3}          A23
+a{4}            A4
+b{2,}           B2
+"x y"|\u{3bb}   XY_OR_LAMBDA
+(cd)+e?         CD
+[a-]            A_OR_DASH
+[^a-c\s]        NOT_ABC
+" "             skip
+"#;
+        assert_eq!(
+            lex(spec, "aaa aaaa aa bbbbb d x y λ cdcde - a"),
+            tokens(&[
+                ("A23", "aaa"),
+                ("A4", "aaaa"),
+                ("A23", "aa"),
+                ("B2", "bbbbb"),
+                ("NOT_ABC", "d"),
+                ("XY_OR_LAMBDA", "x y"),
+                ("XY_OR_LAMBDA", "λ"),
+                ("CD", "cdcde"),
+                ("A_OR_DASH", "-"),
+                ("A_OR_DASH", "a"),
+            ])
+        );
+    }
+
+    #[test]
+    fn errors_with_line_numbers() {
+        let cases = vec![
+            ("a [0-9]\n%%\n{b}+ NUMBER\n", 3, "undefined definition `b`"),
+            ("%%\n\n[a-z NAME\n", 3, "unclosed character class"),
+            ("%%\n[z-a] NAME\n", 2, "range `z-a` is out of order"),
+            ("%%\n(ab NAME\n", 2, "unclosed group"),
+            ("%%\nab) NAME\n", 2, "unmatched `)`"),
+            ("%%\n*a NAME\n", 2, "`*` has nothing to repeat"),
+            (
+                "%%\na{3,1} NAME\n",
+                2,
+                "repetition maximum 1 is below its minimum 3",
+            ),
+            (
+                "%%\nb B\na{100000000} NAME\n",
+                3,
+                "repetition count 100000000 is above the maximum 1000",
+            ),
+            (
+                "%%\na{2,1001} NAME\n",
+                2,
+                "repetition count 1001 is above the maximum 1000",
+            ),
+            ("%%\n\"ab NAME\n", 2, "unclosed string"),
+            ("%%\nab\n", 2, "missing token name after the pattern"),
+            (
+                "%%\nab NAME jump x\n",
+                2,
+                "invalid action `jump x`, expected `push MODE`, `pop` or `switch MODE`",
+            ),
+            ("%%\na A\nb B push nowhere\n", 3, "unknown mode `nowhere`"),
+            ("a x\na y\n%%\n", 2, "definition `a` is defined twice"),
+            ("1a x\n%%\n", 1, "invalid definition name `1a`"),
+            ("a\n%%\n", 1, "definition `a` has no pattern"),
+            ("a x y\n%%\n", 1, "unexpected `y` after the pattern"),
+            ("%mode m\n%%\n", 1, "`%mode` before `%%`"),
+            ("%%\n%mode\n", 2, "expected `%mode NAME`"),
+            ("%%\n%%\n", 2, "unexpected second `%%`"),
+            ("a x\n\nb y\n", 3, "missing `%%` before the rules"),
+        ];
+        for (spec, line, message) in cases {
+            assert_eq!(
+                error(spec),
+                SpecFileError {
+                    line,
+                    message: message.to_string(),
+                },
+                "{:?}",
+                spec
+            );
+        }
+        assert_eq!(
+            error("%%\n[] NAME\n").to_string(),
+            "error on line 2: empty character class"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use token_scanner::formats::table::TableParseError;
+    use token_scanner::{pre_defined_fa, FiniteAutomaton, DFA, NFA};
+
+    fn strings(s: &str) -> Vec<String> {
+        s.chars().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn print_dfa() {
+        let dfa = pre_defined_fa::bin_str_div_by_3();
+        assert_eq!(
+            dfa.transition_table().to_string(),
+            "        0  1\n->*  0  0  1\n     1  2  0\n     2  1  2\n"
+        );
+    }
+
+    #[test]
+    fn print_nfa() {
+        let nfa = NFA::from_formal(
+            hashset! {0, 1, 2},
+            hashset! {'a', 'b'},
+            0,
+            hashset! {2},
+            hashmap! {
+                0 => hashmap! {
+                    Some('a') => hashset! {0, 1},
+                    Some('b') => hashset! {0},
+                },
+                1 => hashmap! {
+                    None => hashset! {2},
+                },
+            },
+        );
+        assert_eq!(
+            nfa.transition_table().to_string(),
+            "       a      b    ε\n->  0  {0,1}  {0}  {}\n    1  {}     {}   {2}\n*   2  {}     {}   {}\n"
+        );
+    }
+
+    #[test]
+    fn parse_nfa() {
+        let nfa = NFA::from_transition_table(
+            "
+            # Strings over {a, b} ending in \"ab\".
+                   a         b     ε
+            -> q0  {q0, q1}  {q0}  {}
+               q1  {}        {q2}  {}
+            *  q2  {}        {}    {}
+            ",
+        )
+        .unwrap();
+        assert!(nfa.accept(strings("abab").into_iter()));
+        assert!(!nfa.accept(strings("aba").into_iter()));
+        assert_eq!(*nfa.accepted_states(), hashset! {"q2".to_string()});
+    }
+
+    #[test]
+    fn round_trip() {
+        let dfa = DFA::from_transition_table(
+            &pre_defined_fa::bin_str_div_by_3()
+                .transition_table()
+                .to_string(),
+        )
+        .unwrap();
+        for (input, expected) in [("", true), ("11", true), ("110", true), ("111", false)] {
+            assert_eq!(dfa.accept(strings(input).into_iter()), expected);
+        }
+
+        let nfa = NFA::from_transition_table(
+            "
+                 a
+            ->   s  {t}
+            *    t  {}
+            ",
+        )
+        .unwrap();
+        let printed = nfa.transition_table().to_string();
+        let reparsed = NFA::from_transition_table(&printed).unwrap();
+        assert_eq!(reparsed.transition_table().to_string(), printed);
+    }
+
+    #[test]
+    fn invalid_tables() {
+        assert!(matches!(
+            NFA::from_transition_table("a\n-> s {s} {s}"),
+            Err(TableParseError::Syntax { line: 2, .. })
+        ));
+        assert!(matches!(
+            NFA::from_transition_table("a\ns {s}"),
+            Err(TableParseError::Syntax { .. })
+        ));
+        assert!(matches!(
+            NFA::from_transition_table("a\n-> s {s"),
+            Err(TableParseError::Syntax { line: 2, .. })
+        ));
+        assert!(matches!(
+            NFA::from_transition_table("a\n-> s {t}"),
+            Err(TableParseError::InvalidAutomaton(_))
+        ));
+        assert!(matches!(
+            DFA::from_transition_table("a b\n-> s {s,t} s\n* t t t"),
+            Err(TableParseError::Syntax { line: 2, .. })
+        ));
+        assert!(matches!(
+            DFA::from_transition_table("a b\n-> s {} s"),
+            Err(TableParseError::InvalidAutomaton(_))
+        ));
+    }
+
+    #[test]
+    fn header_errors_report_header_line() {
+        let cases = vec![
+            ("\n# comment\n", 2, "missing header row"),
+            (
+                "# a comment\n\n  a a\n-> s s s\n",
+                3,
+                "duplicate alphabet in header row",
+            ),
+            (
+                "\n\na\ns s\n",
+                3,
+                "expected exactly one start state marked with ->, found 0",
+            ),
+        ];
+        for (table, line, message) in cases {
+            assert_eq!(
+                NFA::from_transition_table(table).err(),
+                Some(TableParseError::Syntax {
+                    line,
+                    message: message.to_string(),
+                }),
+                "{:?}",
+                table
+            );
+        }
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use token_scanner::automaton::tagged::{TaggedDFA, TaggedNFA};
+    use token_scanner::{FiniteAutomaton, InvalidAutomatonError, DFA, NFA};
+
+    /// "ab" tagged "KEYWORD" and `[ab]+` tagged "WORD", where "KEYWORD" has the higher priority.
+    fn keyword_or_word() -> TaggedNFA<char, usize, &'static str> {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {3, 4},
+            hashmap! {
+                0 => hashmap! {Some('a') => hashset! {1, 4}, Some('b') => hashset! {4}},
+                1 => hashmap! {Some('b') => hashset! {3}},
+                4 => hashmap! {Some('a') => hashset! {4}, Some('b') => hashset! {4}},
+            },
+        );
+        TaggedNFA::new(nfa, hashmap! {3 => "KEYWORD", 4 => "WORD"}).unwrap()
+    }
+
+    #[test]
+    fn to_dfa_resolves_by_priority() {
+        let nfa = keyword_or_word();
+        let dfa = nfa.to_dfa();
+        for input in ["", "a", "b", "ab", "ba", "abb", "aab"] {
+            assert_eq!(
+                dfa.accept_tag(input.chars()),
+                nfa.accept_tag(input.chars()),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(dfa.accept_tag("ab".chars()), Some(&"KEYWORD"));
+        assert_eq!(dfa.accept_tag("abb".chars()), Some(&"WORD"));
+        assert_eq!(dfa.accept_tag("".chars()), None);
+    }
+
+    #[test]
+    fn minimized_keeps_differently_tagged_states_apart() {
+        // States 1 and 2 are equivalent as an untagged DFA.
+        let dfa = DFA::from_map(
+            0,
+            hashset! {1, 2},
+            hashmap! {
+                0 => hashmap! {'a' => 1, 'b' => 2},
+                1 => hashmap! {'a' => 3, 'b' => 3},
+                2 => hashmap! {'a' => 3, 'b' => 3},
+                3 => hashmap! {'a' => 3, 'b' => 3},
+            },
+        );
+        assert_eq!(dfa.minimized().states().len(), 3);
+
+        let tagged: TaggedDFA<char, usize, u8> =
+            TaggedDFA::new(dfa, hashmap! {1 => 0, 2 => 1}).unwrap();
+        let minimized = tagged.minimized();
+        assert_eq!(minimized.automaton().states().len(), 4);
+        assert_eq!(minimized.accept_tag("a".chars()), Some(&0));
+        assert_eq!(minimized.accept_tag("b".chars()), Some(&1));
+
+        let same_tags: TaggedDFA<char, usize, u8> =
+            TaggedDFA::new(tagged.automaton().clone(), hashmap! {1 => 0, 2 => 0}).unwrap();
+        assert_eq!(same_tags.minimized().automaton().states().len(), 3);
+    }
+
+    #[test]
+    fn relabeled_keeps_tags() {
+        let dfa = keyword_or_word().to_dfa().minimized().relabeled();
+        assert_eq!(dfa.automaton().start_state(), 0);
+        assert_eq!(
+            dfa.tags()
+                .keys()
+                .cloned()
+                .collect::<std::collections::HashSet<usize>>(),
+            dfa.automaton().accepted_states().clone()
+        );
+        assert_eq!(dfa.accept_tag("ab".chars()), Some(&"KEYWORD"));
+        assert_eq!(dfa.accept_tag("bab".chars()), Some(&"WORD"));
+        assert_eq!(dfa.accept_tag("c".chars()), None);
+    }
+
+    #[test]
+    fn tags_must_match_accepted_states() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {0 => hashmap! {Some('a') => hashset! {1}}},
+        );
+        let err = TaggedNFA::new(nfa, hashmap! {0 => 'x'}).unwrap_err();
+        assert_eq!(
+            err,
+            InvalidAutomatonError::TaggedStatesMismatch {
+                states: "[0, 1]".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use maplit::{hashmap, hashset};
+    use rand::Rng;
+    use token_scanner::unicode::char_automaton::CharAutomaton;
+    use token_scanner::unicode::char_class::{CharClass, CharRange};
+    use token_scanner::unicode::GeneralCategory;
+    use token_scanner::{DenseDFA, FiniteAutomaton, NFA};
+
+    fn any_string() -> NFA<CharClass, usize> {
+        NFA::from_map(
+            0,
+            hashset! {0},
+            hashmap! {0 => hashmap! {Some(CharClass::any()) => hashset! {0}}},
+        )
+    }
+
+    fn random_char<R: Rng>(rng: &mut R) -> char {
+        let ranges = [
+            (0x0, 0x7F),
+            (0x80, 0x7FF),
+            (0x391, 0x3C9),
+            (0x800, 0xD7FF),
+            (0xE000, 0xFFFF),
+            (0x4E00, 0x4E20),
+            (0x10000, 0x10FFFF),
+            (0x1F600, 0x1F64F),
+        ];
+        let (start, end) = ranges[rng.gen_range(0, ranges.len())];
+        std::char::from_u32(rng.gen_range(start, end + 1)).unwrap()
+    }
+
+    #[test]
+    fn single_characters() {
+        let letters_and_digits = CharClass::from_general_categories(&[
+            GeneralCategory::UppercaseLetter,
+            GeneralCategory::LowercaseLetter,
+            GeneralCategory::OtherLetter,
+        ])
+        .union(&CharClass::from(CharRange::new('0', '9')));
+        let nfa = NFA::from_map(
+            0,
+            hashset! {1},
+            hashmap! {0 => hashmap! {Some(letters_and_digits.clone()) => hashset! {1}}},
+        );
+        let dense = DenseDFA::from_dfa(&nfa.to_utf8().to_dfa());
+        let mut buffer = [0; 4];
+        for c in '\0'..=std::char::MAX {
+            let bytes = c.encode_utf8(&mut buffer).bytes();
+            assert_eq!(
+                dense.accept(bytes),
+                letters_and_digits.contains(c),
+                "{:?}",
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn random_strings() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {2},
+            hashmap! {
+                0 => hashmap! {
+                    Some(CharClass::from(CharRange::new('a', 'z'))) => hashset! {0, 1},
+                    Some(CharClass::from(CharRange::new('\u{80}', '\u{FFFF}'))) => hashset! {1},
+                    None => hashset! {1},
+                },
+                1 => hashmap! {
+                    Some(CharClass::from(CharRange::new('\u{391}', '\u{10FFFF}'))) => hashset! {1, 2},
+                    Some(CharClass::from('\u{7F}')) => hashset! {2},
+                },
+                2 => hashmap! {
+                    Some(CharClass::any().difference(&CharClass::from(CharRange::new('\u{E000}', '\u{FFFF}')))) => hashset! {0},
+                },
+            },
+        );
+        let bytes_nfa = nfa.to_utf8();
+        let bytes_dfa = bytes_nfa.to_dfa();
+        let mut rng = rand::thread_rng();
+        let mut accepted = 0;
+        for _ in 0..500 {
+            let len = rng.gen_range(0, 6);
+            let input: String = (0..len).map(|_| random_char(&mut rng)).collect();
+            let expected = nfa.accept_chars(input.chars());
+            accepted += expected as usize;
+            assert_eq!(bytes_nfa.accept(input.bytes()), expected, "{:?}", input);
+            assert_eq!(bytes_dfa.accept(input.bytes()), expected, "{:?}", input);
+        }
+        assert!(accepted > 0);
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        let dense = DenseDFA::from_dfa(&any_string().to_utf8().to_dfa());
+        let valid = "aé€😀\u{10FFFF}\u{D7FF}\u{E000}";
+        assert!(dense.accept(valid.bytes()));
+        let invalid: Vec<Vec<u8>> = vec![
+            // Lone continuation byte.
+            vec![0x80],
+            // Overlong encodings of '/' and NUL.
+            vec![0xC0, 0xAF],
+            vec![0xE0, 0x80, 0x80],
+            vec![0xF0, 0x80, 0x80, 0x80],
+            // Surrogate.
+            vec![0xED, 0xA0, 0x80],
+            // Beyond U+10FFFF.
+            vec![0xF4, 0x90, 0x80, 0x80],
+            vec![0xF5, 0x80, 0x80, 0x80],
+            // Truncated sequences.
+            vec![0xE2, 0x82],
+            vec![b'a', 0xF0, 0x9F, 0x98],
+            vec![0xFF],
+        ];
+        for bytes in invalid {
+            assert!(std::str::from_utf8(&bytes).is_err());
+            assert!(!dense.accept(bytes.iter().cloned()), "{:x?}", bytes);
+        }
+    }
+
+    #[test]
+    fn plain_char_alphabets() {
+        let nfa = NFA::from_map(
+            0,
+            hashset! {2},
+            hashmap! {
+                0 => hashmap! {Some('λ') => hashset! {1}},
+                1 => hashmap! {Some('x') => hashset! {2}},
+            },
+        );
+        let bytes = nfa.to_utf8();
+        assert!(bytes.accept("λx".bytes()));
+        assert!(!bytes.accept("λy".bytes()));
+        assert_eq!(bytes.states().len(), 4);
+    }
+}